@@ -0,0 +1,41 @@
+struct Uniforms {
+    color: vec4<f32>,
+    resolution: vec2<f32>,
+    // seconds since the start, the whole seconds in x and the fraction in y,
+    // as a single f32 loses the fraction after a few hours
+    time: vec2<f32>,
+    scale_factor: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) brightness: f32,
+}
+
+const TURN: f32 = 6.2831853;
+const THIRD_OF_TURN: f32 = 2.0943951;
+
+// the time in seconds wrapped to a period, keeping the fraction precise
+fn time_in(period: f32) -> f32 {
+    return (uniforms.time.x % period) + uniforms.time.y;
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // rotating triangle in the center, 120 logical pixels to the corners
+    let angle = time_in(TURN) + f32(index) * THIRD_OF_TURN;
+    let offset = vec2<f32>(cos(angle), sin(angle)) * 120.0 * uniforms.scale_factor;
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(offset * 2.0 / uniforms.resolution, 0.0, 1.0);
+    out.brightness = 0.75 + 0.25 * sin(time_in(TURN / 2.0) * 2.0 + f32(index) * THIRD_OF_TURN);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(uniforms.color.rgb * in.brightness, uniforms.color.a);
+}
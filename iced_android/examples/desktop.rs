@@ -0,0 +1,6 @@
+use iced_android::controls::Controls;
+
+// cargo run --example desktop
+fn main() {
+    iced_android::run(Controls::new);
+}
@@ -0,0 +1,112 @@
+//! Runs the [`Task`](iced_winit::runtime::Task)s returned from `Program::update`,
+//! and the subscriptions of the program.
+
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc;
+use futures::Sink;
+use iced_futures::backend::default::Executor;
+use iced_futures::Executor as _;
+use iced_wgpu::graphics::{text, Viewport};
+use iced_widget::Renderer;
+use iced_winit::runtime::program::State;
+use iced_winit::runtime::{clipboard, Action, Debug};
+use iced_winit::winit::event_loop::EventLoopProxy;
+
+use crate::{Program, UserEvent};
+
+pub(crate) type Runtime<M> = iced_futures::Runtime<Executor, Proxy<M>, Action<M>>;
+
+pub(crate) fn runtime<M: Send + 'static>(proxy: EventLoopProxy<UserEvent>) -> Runtime<M> {
+    let executor = Executor::new().expect("Should create executor");
+
+    Runtime::new(
+        executor,
+        Proxy {
+            raw: proxy,
+            _message: PhantomData,
+        },
+    )
+}
+
+/// Sends the actions produced by tasks and subscriptions back to the event loop,
+/// as [`UserEvent::Action`].
+pub(crate) struct Proxy<M> {
+    raw: EventLoopProxy<UserEvent>,
+    _message: PhantomData<fn(M)>,
+}
+
+impl<M> Clone for Proxy<M> {
+    fn clone(&self) -> Self {
+        Self {
+            raw: self.raw.clone(),
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<M: Send + 'static> Sink<Action<M>> for Proxy<M> {
+    type Error = mpsc::SendError;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, action: Action<M>) -> Result<(), Self::Error> {
+        // the event loop is gone, nobody is waiting for the result
+        let _ = self.raw.send_event(UserEvent::Action(Box::new(action)));
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Runs the actions which only need the program state, messages are queued
+/// until the next update. Window, system and exit actions are returned.
+pub(crate) fn perform<P: Program>(
+    action: Action<P::Message>,
+    state: &mut State<P>,
+    renderer: &mut Renderer,
+    viewport: &Viewport,
+    clipboard: &mut dyn iced_core::Clipboard,
+    debug: &mut Debug,
+) -> Option<Action<P::Message>> {
+    match action {
+        Action::Output(message) => {
+            state.queue_message(message);
+        }
+        Action::LoadFont { bytes, channel } => {
+            text::font_system()
+                .write()
+                .expect("Write font system")
+                .load_font(bytes);
+
+            let _ = channel.send(Ok(()));
+        }
+        Action::Widget(operation) => {
+            state.operate(
+                renderer,
+                std::iter::once(operation),
+                viewport.logical_size(),
+                debug,
+            );
+        }
+        Action::Clipboard(clipboard::Action::Read { target, channel }) => {
+            let _ = channel.send(clipboard.read(target));
+        }
+        Action::Clipboard(clipboard::Action::Write { target, contents }) => {
+            clipboard.write(target, contents);
+        }
+        action => return Some(action),
+    }
+
+    None
+}
@@ -0,0 +1,234 @@
+use std::ffi::CString;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use iced_winit::winit;
+use winit::event_loop::{EventLoop, EventLoopProxy};
+use winit::platform::android::activity::AndroidApp;
+use winit::platform::android::EventLoopBuilderExtAndroid;
+use winit::window::{Window, WindowAttributes};
+
+use iced_winit::core::Color;
+
+use crate::{gesture, java, AppConfig, Appearance, Program, UserEvent};
+
+pub use crate::clipboard::Clipboard;
+
+// android.content.res.Configuration
+const UI_MODE_NIGHT_MASK: i32 = 0x30;
+const UI_MODE_NIGHT_YES: i32 = 0x20;
+
+// Build.VERSION_CODES.S, the first one with dynamic colors
+const DYNAMIC_COLORS_SDK: i32 = 31;
+
+pub(crate) const SYSTEM_FONT_DIRS: &[&str] = &["/system/fonts"];
+// cosmic-text defaults to Fira Sans and Fira Mono, which android doesn't have
+pub(crate) const SANS_SERIF_FAMILY: Option<&str> = Some("Roboto");
+pub(crate) const MONOSPACE_FAMILY: Option<&str> = Some("Droid Sans Mono");
+
+// for the AssetManager
+static ANDROID_APP: OnceLock<AndroidApp> = OnceLock::new();
+
+/// Runs the event loop until the activity is destroyed.
+/// Call it from the `android_main` of your cdylib.
+///
+/// `new_program` is called once, when the window is created for the first time.
+/// The program, as well as the gpu device, is kept alive across suspend/resume.
+pub fn run<P: Program>(
+    android_app: AndroidApp,
+    new_program: impl FnOnce(EventLoopProxy<UserEvent>) -> P + 'static,
+) {
+    run_with_config(android_app, AppConfig::default(), new_program);
+}
+
+/// Same as [`run`], with custom [`AppConfig`].
+pub fn run_with_config<P: Program>(
+    android_app: AndroidApp,
+    config: AppConfig,
+    new_program: impl FnOnce(EventLoopProxy<UserEvent>) -> P + 'static,
+) {
+    let logger_config = android_logger::Config::default().with_max_level(config.log_level);
+    android_logger::init_once(logger_config);
+
+    log::info!("android_main started");
+
+    let _ = ANDROID_APP.set(android_app.clone());
+
+    let event_loop = EventLoop::with_user_event()
+        .with_android_app(android_app)
+        .build()
+        .expect("Should build event loop");
+
+    crate::run_app(event_loop, config, new_program);
+}
+
+pub(crate) fn window_attributes() -> WindowAttributes {
+    Window::default_attributes()
+}
+
+pub(crate) fn clipboard(_window: Arc<Window>) -> Clipboard {
+    Clipboard {}
+}
+
+pub(crate) fn appearance(_window: &Window) -> Option<Appearance> {
+    match java::ui_mode() {
+        Ok(ui_mode) if ui_mode & UI_MODE_NIGHT_MASK == UI_MODE_NIGHT_YES => Some(Appearance::Dark),
+        Ok(_) => Some(Appearance::Light),
+        Err(error) => {
+            log::error!("Error reading uiMode: {error}");
+            None
+        }
+    }
+}
+
+pub(crate) fn system_colors(names: &[&str]) -> Option<Vec<Color>> {
+    match java::sdk_int() {
+        Ok(sdk) if sdk >= DYNAMIC_COLORS_SDK => {}
+        Ok(sdk) => {
+            log::info!("Dynamic colors need Android 12, sdk version is {sdk}");
+            return None;
+        }
+        Err(error) => {
+            log::error!("Error reading the sdk version: {error}");
+            return None;
+        }
+    }
+
+    match java::system_colors(names) {
+        Ok(colors) => Some(colors.into_iter().map(argb_to_color).collect()),
+        Err(error) => {
+            log::error!("Error reading system colors: {error}");
+            None
+        }
+    }
+}
+
+// ViewConfiguration is in pixels, the recognizer in logical pixels
+pub(crate) fn gesture_config(scale_factor: f64) -> Option<gesture::Config> {
+    let configuration = match java::view_configuration() {
+        Ok(configuration) => configuration,
+        Err(error) => {
+            log::error!("Error reading ViewConfiguration: {error}");
+            return None;
+        }
+    };
+
+    let logical = |pixels: i32| (f64::from(pixels) / scale_factor) as f32;
+    let millis = |millis: i32| Duration::from_millis(millis.max(0) as u64);
+
+    Some(gesture::Config {
+        touch_slop: logical(configuration.touch_slop),
+        double_tap_slop: logical(configuration.double_tap_slop),
+        double_tap_timeout: millis(configuration.double_tap_timeout),
+        long_press_timeout: millis(configuration.long_press_timeout),
+        min_fling_velocity: logical(configuration.minimum_fling_velocity),
+        max_fling_velocity: logical(configuration.maximum_fling_velocity),
+    })
+}
+
+fn argb_to_color(argb: i32) -> Color {
+    let [a, r, g, b] = argb.to_be_bytes();
+    Color::from_rgba8(r, g, b, f32::from(a) / 255.0)
+}
+
+// cleared by the system when it's low on storage, and on app updates
+pub(crate) fn cache_dir() -> Option<PathBuf> {
+    match java::cache_dir() {
+        Ok(dir) => Some(PathBuf::from(dir)),
+        Err(error) => {
+            log::error!("Error reading the cache dir: {error}");
+            None
+        }
+    }
+}
+
+// the assets are read from the apk
+pub(crate) fn set_assets_dir(_dir: &Path) {}
+
+pub(crate) fn read_asset(path: &str) -> io::Result<Vec<u8>> {
+    let android_app = ANDROID_APP
+        .get()
+        .ok_or_else(|| io::Error::other("assets are available after run is called"))?;
+
+    let c_path =
+        CString::new(path).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+
+    let mut asset = android_app
+        .asset_manager()
+        .open(&c_path)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such asset"))?;
+
+    let mut bytes = Vec::new();
+    asset.read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+pub(crate) fn show_keyboard() {
+    java::call_instance_method("showKeyboard");
+}
+
+pub(crate) fn hide_keyboard() {
+    java::call_instance_method("hideKeyboard");
+}
+
+// GameActivity keeps the text typed with the soft keyboard in the buffer of its
+// input connection. winit only logs that it changed, so it's read after every
+// iteration of the event loop, and emptied of the committed text.
+#[cfg(feature = "game-activity")]
+pub(crate) fn ime_events(ime: &mut crate::ime::Ime) -> Vec<winit::event::Ime> {
+    use winit::platform::android::activity::input::{TextInputState, TextSpan};
+
+    let Some(android_app) = ANDROID_APP.get() else {
+        return Vec::new();
+    };
+
+    // the input method starts over, the composition stays in the widget
+    if ime.take_reset() {
+        android_app.set_text_input_state(TextInputState {
+            text: String::new(),
+            selection: TextSpan { start: 0, end: 0 },
+            compose_region: None,
+        });
+        return Vec::new();
+    }
+
+    let state = android_app.text_input_state();
+    let composing = state
+        .compose_region
+        .map(|span| byte_index(&state.text, span.start)..byte_index(&state.text, span.end));
+
+    let Some(events) = ime.buffer(&state.text, composing) else {
+        return Vec::new();
+    };
+
+    let text = ime.composing_text().to_string();
+    let len = text.encode_utf16().count();
+    android_app.set_text_input_state(TextInputState {
+        text,
+        selection: TextSpan {
+            start: len,
+            end: len,
+        },
+        compose_region: (len > 0).then_some(TextSpan { start: 0, end: len }),
+    });
+
+    events
+}
+
+// spans of the buffer are java string indices, in utf-16 code units
+#[cfg(feature = "game-activity")]
+fn byte_index(text: &str, utf16_index: usize) -> usize {
+    let mut units = 0;
+
+    for (index, c) in text.char_indices() {
+        if units >= utf16_index {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+
+    text.len()
+}
@@ -0,0 +1,18 @@
+//! Files bundled with the app.
+//!
+//! On android they are read from the apk with the `AssetManager`,
+//! on desktop from [`AppConfig::assets_dir`](crate::AppConfig::assets_dir).
+
+use std::io;
+
+use crate::platform;
+
+/// Reads the whole file, `path` is relative to the assets root, e.g. `shaders/animated.wgsl`.
+pub fn read(path: &str) -> io::Result<Vec<u8>> {
+    platform::read_asset(path)
+}
+
+pub fn read_to_string(path: &str) -> io::Result<String> {
+    String::from_utf8(read(path)?)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
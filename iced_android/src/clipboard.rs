@@ -2,7 +2,7 @@ use jni::objects::{JObject, JString, JValue};
 
 use crate::java::{get_env, get_vm};
 
-pub(crate) struct Clipboard {}
+pub struct Clipboard {}
 
 impl iced_core::Clipboard for Clipboard {
     fn read(&self, _kind: iced_core::clipboard::Kind) -> Option<String> {
@@ -0,0 +1,213 @@
+use std::path::PathBuf;
+
+use iced_wgpu::wgpu;
+use iced_winit::core::{Font, Pixels, Theme};
+use iced_winit::winit::event_loop::ControlFlow;
+use log::LevelFilter;
+
+use crate::{gesture, kinetic};
+
+/// Startup settings of the renderer and the runtime.
+///
+/// ```no_run
+/// use iced_android::AppConfig;
+/// use iced_wgpu::wgpu;
+/// use iced_winit::core::Theme;
+///
+/// let config = AppConfig::default()
+///     .present_mode(wgpu::PresentMode::Mailbox)
+///     .default_text_size(18)
+///     .theme(Theme::Dark);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    pub(crate) rendering: Rendering,
+    pub(crate) backends: wgpu::Backends,
+    pub(crate) present_mode: wgpu::PresentMode,
+    pub(crate) desired_maximum_frame_latency: u32,
+    pub(crate) default_font: Font,
+    pub(crate) fonts: Vec<String>,
+    pub(crate) system_fonts: bool,
+    pub(crate) fallback_fonts: Vec<String>,
+    pub(crate) default_text_size: Pixels,
+    pub(crate) control_flow: ControlFlow,
+    pub(crate) theme: Option<Theme>,
+    pub(crate) gestures: Option<gesture::Config>,
+    pub(crate) kinetic: Option<kinetic::Config>,
+    pub(crate) log_level: LevelFilter,
+    pub(crate) assets_dir: PathBuf,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            rendering: Rendering::Auto,
+            backends: wgpu::Backends::all(),
+            present_mode: wgpu::PresentMode::AutoVsync,
+            desired_maximum_frame_latency: 2,
+            default_font: Font::default(),
+            fonts: Vec::new(),
+            system_fonts: false,
+            fallback_fonts: Vec::new(),
+            default_text_size: Pixels::from(16),
+            control_flow: ControlFlow::Wait,
+            theme: None,
+            gestures: None,
+            kinetic: Some(kinetic::Config::default()),
+            log_level: LevelFilter::Info,
+            assets_dir: PathBuf::from("assets"),
+        }
+    }
+}
+
+/// How the window is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rendering {
+    /// wgpu, or software if there is no suitable adapter or surface format
+    #[default]
+    Auto,
+    /// wgpu only, fails if the gpu can't be used
+    Gpu,
+    /// tiny-skia + softbuffer, the custom wgpu scene is not drawn
+    Software,
+}
+
+impl AppConfig {
+    pub fn rendering(mut self, rendering: Rendering) -> Self {
+        self.rendering = rendering;
+        self
+    }
+
+    pub fn backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    /// Falls back to `AutoVsync` if the surface doesn't support the mode.
+    pub fn present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
+    /// Should be at least 1, typical values are 1 to 3.
+    pub fn desired_maximum_frame_latency(mut self, latency: u32) -> Self {
+        self.desired_maximum_frame_latency = latency;
+        self
+    }
+
+    /// E.g. `Font::with_name("Noto Sans")`, the family has to be one of the
+    /// loaded fonts, see [`font`](Self::font) and [`system_fonts`](Self::system_fonts).
+    pub fn default_font(mut self, font: Font) -> Self {
+        self.default_font = font;
+        self
+    }
+
+    /// Loads a font file from the [`assets`](crate::assets) at startup.
+    pub fn font(mut self, path: impl Into<String>) -> Self {
+        self.fonts.push(path.into());
+        self
+    }
+
+    /// Loads the fonts from `/system/fonts` on android, for the scripts missing
+    /// in the bundled fonts. On desktop the system fonts are always available.
+    pub fn system_fonts(mut self, enabled: bool) -> Self {
+        self.system_fonts = enabled;
+        self
+    }
+
+    /// Families tried in this order when the default font has no glyph
+    /// for a character, before the rest of the loaded fonts.
+    pub fn fallback_fonts<I>(mut self, families: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.fallback_fonts = families.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn default_text_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.default_text_size = size.into();
+        self
+    }
+
+    pub fn control_flow(mut self, control_flow: ControlFlow) -> Self {
+        self.control_flow = control_flow;
+        self
+    }
+
+    /// Used instead of following the system light or dark mode,
+    /// unless [`Program::theme`](crate::Program::theme) returns one.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Thresholds of the gesture recognizer, from `ViewConfiguration` by default on android.
+    pub fn gestures(mut self, gestures: gesture::Config) -> Self {
+        self.gestures = Some(gestures);
+        self
+    }
+
+    /// Momentum scrolling of scrollables after a fling, `None` disables it.
+    pub fn kinetic_scrolling(mut self, kinetic: Option<kinetic::Config>) -> Self {
+        self.kinetic = kinetic;
+        self
+    }
+
+    pub fn log_level(mut self, level: LevelFilter) -> Self {
+        self.log_level = level;
+        self
+    }
+
+    /// Where [`assets`](crate::assets) are read from on desktop, `assets` by default.
+    /// On android they always come from the apk.
+    pub fn assets_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.assets_dir = dir.into();
+        self
+    }
+
+    /// Surface configuration, with the settings checked against
+    /// what the adapter supports for this surface.
+    pub(crate) fn surface_configuration(
+        &self,
+        capabilities: &wgpu::SurfaceCapabilities,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> wgpu::SurfaceConfiguration {
+        // Auto* modes are always supported, the rest is optional
+        let present_mode = if capabilities.present_modes.contains(&self.present_mode)
+            || matches!(
+                self.present_mode,
+                wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync
+            ) {
+            self.present_mode
+        } else {
+            log::warn!(
+                "Present mode {:?} is not supported, supported modes: {:?}, using AutoVsync",
+                self.present_mode,
+                capabilities.present_modes
+            );
+            wgpu::PresentMode::AutoVsync
+        };
+
+        let desired_maximum_frame_latency = if self.desired_maximum_frame_latency == 0 {
+            log::warn!("Frame latency should be at least 1, using 1");
+            1
+        } else {
+            self.desired_maximum_frame_latency
+        };
+
+        wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width,
+            height,
+            present_mode,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+            desired_maximum_frame_latency,
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use iced_widget::Renderer;
+use iced_widget::{
+    button, column, container, pick_list, responsive, row, slider, text, text_editor, text_input,
+    vertical_space, PickList, Slider, Space,
+};
+use std::time::Duration;
+
+use iced_futures::Subscription;
+use iced_widget::text_input::Value;
+use iced_winit::core::{Alignment, Color, Element, Length, Theme};
+use iced_winit::runtime::{clipboard, Program, Task};
+use iced_winit::winit::event_loop::EventLoopProxy;
+
+use crate::scene::Animated;
+use crate::shortcut::{self, History, Shortcut};
+use crate::{theme, time, Appearance, UserEvent};
+
+const EXAMPLES: [Example; 3] = [Example::Integration, Example::Counter, Example::TextEditor];
+
+// below this height the margins don't fit, e.g. on a watch
+const COMPACT_HEIGHT: f32 = 360.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Example {
+    Integration,
+    Counter,
+    TextEditor,
+}
+
+impl std::fmt::Display for Example {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+pub struct Controls {
+    background_color: Color,
+    input: String,
+    value: i32,
+    // time spent in the counter example, the clock stops in background
+    seconds: u64,
+    selected_example: Example,
+    editor: text_editor::Content<Renderer>,
+    // for undo and redo with a keyboard
+    input_history: History<String>,
+    // the text with the line and byte index of the cursor
+    editor_history: History<(String, (usize, usize))>,
+    // an input method is composing, its steps aren't recorded
+    composing: bool,
+    // there is no event loop when running headless
+    proxy: Option<EventLoopProxy<UserEvent>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    RedChanged(f32),
+    GreenChanged(f32),
+    BlueChanged(f32),
+    InputChanged(String),
+    EditorAction(text_editor::Action),
+    ExampleSelected(Example),
+    CopyColor,
+    AppearanceChanged(Appearance),
+    Shortcut(Shortcut),
+    Composing(bool),
+    Tick,
+    Inc,
+    Dec,
+}
+
+impl Controls {
+    pub fn new(proxy: EventLoopProxy<UserEvent>) -> Controls {
+        Controls {
+            proxy: Some(proxy),
+            ..Controls::default()
+        }
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn seconds(&self) -> u64 {
+        self.seconds
+    }
+
+    pub fn selected_example(&self) -> Example {
+        self.selected_example
+    }
+
+    pub fn editor_text(&self) -> String {
+        self.editor.text()
+    }
+
+    // of the text field in the example on the screen
+    fn undo_redo(&mut self, shortcut: Shortcut) {
+        match self.selected_example {
+            Example::Integration => {
+                let current = self.input.clone();
+                let restored = match shortcut {
+                    Shortcut::Undo => self.input_history.undo(current),
+                    Shortcut::Redo => self.input_history.redo(current),
+                };
+                if let Some(input) = restored {
+                    self.input = input;
+                }
+            }
+            Example::TextEditor => {
+                let current = (self.editor.text(), self.editor.cursor_position());
+                let restored = match shortcut {
+                    Shortcut::Undo => self.editor_history.undo(current),
+                    Shortcut::Redo => self.editor_history.redo(current),
+                };
+                if let Some((text, cursor)) = restored {
+                    self.editor = text_editor::Content::with_text(&text);
+                    move_cursor(&mut self.editor, cursor);
+                }
+            }
+            Example::Counter => {}
+        }
+    }
+
+    fn send_event(&self, event: UserEvent) {
+        if let Some(proxy) = &self.proxy {
+            let _ = proxy.send_event(event);
+        }
+    }
+}
+
+impl Default for Controls {
+    fn default() -> Controls {
+        Controls {
+            background_color: Color::BLACK,
+            input: String::default(),
+            value: 0,
+            seconds: 0,
+            selected_example: Example::Integration,
+            editor: text_editor::Content::new(),
+            input_history: History::default(),
+            editor_history: History::default(),
+            composing: false,
+            proxy: None,
+        }
+    }
+}
+
+impl crate::Program for Controls {
+    type Scene = Animated;
+
+    fn background_color(&self) -> Color {
+        self.background_color
+    }
+
+    fn update_scene(&self, scene: &mut Animated) {
+        // the complementary color stays visible on any background
+        let Color { r, g, b, .. } = self.background_color;
+        scene.set_color(Color::from_rgb(1.0 - r, 1.0 - g, 1.0 - b));
+        // other examples cover the scene with their background
+        scene.set_paused(self.selected_example != Example::Integration);
+    }
+
+    // Material You on Android 12+, otherwise the light or dark theme
+    fn theme(&self, appearance: Appearance) -> Option<Theme> {
+        theme::dynamic(appearance)
+    }
+
+    fn appearance_changed(&self, appearance: Appearance) -> Option<Message> {
+        Some(Message::AppearanceChanged(appearance))
+    }
+
+    fn shortcut(&self, shortcut: Shortcut) -> Option<Message> {
+        Some(Message::Shortcut(shortcut))
+    }
+
+    fn composing(&self, composing: bool) -> Option<Message> {
+        Some(Message::Composing(composing))
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        if self.selected_example == Example::Counter {
+            time::every(Duration::from_secs(1)).map(|_| Message::Tick)
+        } else {
+            Subscription::none()
+        }
+    }
+}
+
+impl Program for Controls {
+    type Theme = Theme;
+    type Message = Message;
+    type Renderer = Renderer;
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Inc => self.value += 1,
+            Message::Dec => self.value -= 1,
+            Message::Tick => self.seconds += 1,
+            Message::ExampleSelected(example) => self.selected_example = example,
+            Message::InputChanged(value) => {
+                let previous = std::mem::replace(&mut self.input, value);
+                if !self.composing {
+                    self.input_history.record(previous);
+                }
+            }
+            Message::RedChanged(r) => self.background_color.r = r,
+            Message::GreenChanged(g) => self.background_color.g = g,
+            Message::BlueChanged(b) => self.background_color.b = b,
+            Message::AppearanceChanged(appearance) => {
+                // the theme follows on its own, the background is
+                // the user's pick from the sliders
+                log::info!("System appearance changed to {appearance:?}");
+            }
+            Message::CopyColor => {
+                return clipboard::write(format!("{:?}", self.background_color));
+            }
+            Message::EditorAction(action) => match action {
+                text_editor::Action::Focus => {
+                    log::info!("Editor focused");
+                    // it's possible to call java::call_instance_method("showKeyboard")
+                    // right here, but needed something to show the usage of user events
+                    self.send_event(UserEvent::ShowKeyboard);
+                }
+                text_editor::Action::Blur => {
+                    log::info!("Editor lost focus");
+                    self.send_event(UserEvent::HideKeyboard);
+                }
+                other => {
+                    if other.is_edit() && !self.composing {
+                        self.editor_history
+                            .record((self.editor.text(), self.editor.cursor_position()));
+                    }
+                    self.editor.perform(other);
+                }
+            },
+            Message::Shortcut(shortcut) => self.undo_redo(shortcut),
+            Message::Composing(composing) => {
+                // the whole composition is undone at once
+                if composing {
+                    match self.selected_example {
+                        Example::Integration => self.input_history.record(self.input.clone()),
+                        Example::TextEditor => self
+                            .editor_history
+                            .record((self.editor.text(), self.editor.cursor_position())),
+                        Example::Counter => {}
+                    }
+                }
+                self.composing = composing;
+            }
+        }
+
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Message, Theme, Renderer> {
+        match self.selected_example {
+            Example::Integration => self.integration(),
+            Example::Counter => self.counter(),
+            Example::TextEditor => self.text_editor(),
+        }
+    }
+}
+
+// `Content::with_text` puts the cursor at the start, it's moved to the line
+// and byte index grapheme by grapheme, as lines can be wrapped
+fn move_cursor(editor: &mut text_editor::Content<Renderer>, (line, index): (usize, usize)) {
+    let text = editor.text();
+    let lines: Vec<&str> = text.split('\n').collect();
+
+    let before: usize = lines
+        .iter()
+        .take(line)
+        .map(|line| Value::new(line).len() + 1)
+        .sum();
+    let within = lines.get(line).map_or(0, |line| {
+        Value::new(line.get(..index).unwrap_or(line)).len()
+    });
+
+    for _ in 0..before + within {
+        editor.perform(text_editor::Action::Move(text_editor::Motion::Right));
+    }
+}
+
+// the label is drawn over the background picked with the sliders,
+// whatever the theme is
+fn readable_on(background: Color) -> Color {
+    let [r, g, b, _] = background.into_linear();
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+
+    if luminance > 0.18 {
+        Color::BLACK
+    } else {
+        Color::WHITE
+    }
+}
+
+fn color_slider<'a>(value: f32, f: impl Fn(f32) -> Message + 'a) -> Slider<'a, f32, Message> {
+    slider(0.0..=1.0, value, f).step(0.01)
+}
+
+impl Controls {
+    fn examples(&self) -> PickList<'_, Example, &[Example], Example, Message> {
+        pick_list(
+            &EXAMPLES[..],
+            Some(self.selected_example),
+            Message::ExampleSelected,
+        )
+    }
+    fn integration(&self) -> Element<'_, Message, Theme, Renderer> {
+        let sliders = row![
+            color_slider(self.background_color.r, Message::RedChanged),
+            color_slider(self.background_color.g, Message::GreenChanged),
+            color_slider(self.background_color.b, Message::BlueChanged),
+        ]
+        .width(Length::Fill)
+        .spacing(20);
+
+        container(
+            column![
+                Space::with_height(20),
+                self.examples(),
+                vertical_space(),
+                row![
+                    text!("{:?}", self.background_color)
+                        .size(14)
+                        .color(readable_on(self.background_color))
+                        .width(Length::Fill),
+                    button(text("Copy").size(14)).on_press(Message::CopyColor),
+                ]
+                .align_y(Alignment::Center),
+                text_input("Placeholder", &self.input).on_input(Message::InputChanged),
+                sliders,
+                Space::with_height(20),
+            ]
+            .align_x(Alignment::Center)
+            .spacing(10),
+        )
+        .padding(10)
+        .into()
+    }
+
+    fn counter(&self) -> Element<'_, Message, Theme, Renderer> {
+        responsive(|size| {
+            let content = if size.height < COMPACT_HEIGHT {
+                column![
+                    self.examples(),
+                    button("Increment").on_press(Message::Inc),
+                    text!("{}", self.value).size(40),
+                    button("Decrement").on_press(Message::Dec),
+                    self.clock(),
+                ]
+                .spacing(5)
+            } else {
+                column![
+                    Space::with_height(30),
+                    self.examples(),
+                    vertical_space(),
+                    button("Increment").on_press(Message::Inc),
+                    text!("{}", self.value).size(50),
+                    button("Decrement").on_press(Message::Dec),
+                    self.clock(),
+                    vertical_space(),
+                    Space::with_height(100),
+                ]
+                .spacing(10)
+            };
+
+            container(content.align_x(Alignment::Center))
+                .center(Length::Fill)
+                .style(add_background)
+                .into()
+        })
+        .into()
+    }
+
+    fn clock(&self) -> Element<'_, Message, Theme, Renderer> {
+        text!("{:02}:{:02}", self.seconds / 60, self.seconds % 60)
+            .size(14)
+            .into()
+    }
+
+    fn text_editor(&self) -> Element<'_, Message, Theme, Renderer> {
+        container(
+            column![
+                Space::with_height(30),
+                self.examples(),
+                vertical_space(),
+                text_editor::<Message, Theme, Renderer>(&self.editor)
+                    .height(400)
+                    .on_action(Message::EditorAction)
+                    .key_binding(shortcut::text_editor),
+                vertical_space(),
+            ]
+            .align_x(Alignment::Center),
+        )
+        .padding(10)
+        .center(Length::Fill)
+        .style(add_background)
+        .into()
+    }
+}
+
+fn add_background(theme: &Theme) -> container::Style {
+    theme.palette().background.into()
+}
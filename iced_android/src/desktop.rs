@@ -0,0 +1,107 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use iced_winit::core::Color;
+use iced_winit::winit;
+use winit::dpi::LogicalSize;
+use winit::event_loop::{EventLoop, EventLoopProxy};
+use winit::window::{Theme, Window, WindowAttributes};
+
+use crate::{gesture, AppConfig, Appearance, Program, UserEvent};
+
+// desktop has a real clipboard, no need to go through java
+pub use iced_winit::Clipboard;
+
+// cosmic-text loads the system fonts on its own
+pub(crate) const SYSTEM_FONT_DIRS: &[&str] = &[];
+pub(crate) const SANS_SERIF_FAMILY: Option<&str> = None;
+pub(crate) const MONOSPACE_FAMILY: Option<&str> = None;
+
+static ASSETS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Runs the event loop until the window is closed.
+/// Desktop counterpart of the android entry point, useful for
+/// iterating on the ui without building an apk.
+///
+/// `new_program` is called once, when the window is created for the first time.
+/// The program, as well as the gpu device, is kept alive across suspend/resume.
+pub fn run<P: Program>(new_program: impl FnOnce(EventLoopProxy<UserEvent>) -> P + 'static) {
+    run_with_config(AppConfig::default(), new_program);
+}
+
+/// Same as [`run`], with custom [`AppConfig`].
+pub fn run_with_config<P: Program>(
+    config: AppConfig,
+    new_program: impl FnOnce(EventLoopProxy<UserEvent>) -> P + 'static,
+) {
+    env_logger::Builder::new()
+        .filter_level(config.log_level)
+        .parse_default_env()
+        .init();
+
+    log::info!("desktop main started");
+
+    set_assets_dir(&config.assets_dir);
+
+    let event_loop = EventLoop::with_user_event()
+        .build()
+        .expect("Should build event loop");
+
+    crate::run_app(event_loop, config, new_program);
+}
+
+pub(crate) fn window_attributes() -> WindowAttributes {
+    // roughly the size of a phone screen
+    Window::default_attributes()
+        .with_title("iced_android")
+        .with_inner_size(LogicalSize::new(412.0, 915.0))
+}
+
+pub(crate) fn clipboard(window: Arc<Window>) -> Clipboard {
+    Clipboard::connect(window)
+}
+
+pub(crate) fn appearance(window: &Window) -> Option<Appearance> {
+    match window.theme()? {
+        Theme::Light => Some(Appearance::Light),
+        Theme::Dark => Some(Appearance::Dark),
+    }
+}
+
+// dynamic colors are android only
+pub(crate) fn system_colors(_names: &[&str]) -> Option<Vec<Color>> {
+    None
+}
+
+// $XDG_CACHE_HOME/iced_android, or ~/.cache/iced_android
+pub(crate) fn cache_dir() -> Option<PathBuf> {
+    let cache_home = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+    Some(cache_home.join("iced_android"))
+}
+
+// the defaults of android are fine for a mouse or a touch screen
+pub(crate) fn gesture_config(_scale_factor: f64) -> Option<gesture::Config> {
+    None
+}
+
+/// Only the first directory is used, for the rest of the process.
+pub(crate) fn set_assets_dir(dir: &Path) {
+    let _ = ASSETS_DIR.set(dir.to_path_buf());
+}
+
+pub(crate) fn read_asset(path: &str) -> io::Result<Vec<u8>> {
+    let assets_dir = ASSETS_DIR.get_or_init(|| AppConfig::default().assets_dir);
+    std::fs::read(assets_dir.join(path))
+}
+
+pub(crate) fn show_keyboard() {
+    log::info!("Soft keyboard is not available on desktop, ignoring show request");
+}
+
+pub(crate) fn hide_keyboard() {
+    log::info!("Soft keyboard is not available on desktop, ignoring hide request");
+}
@@ -0,0 +1,371 @@
+//! Window events turned into the events of the widgets.
+//!
+//! The window runner and the headless harness both go through [`Dispatch`],
+//! so they see exactly the same input. What needs the window, or stands in
+//! for it in the harness, is behind [`Host`].
+
+use std::time::Instant;
+
+use iced_winit::core::{keyboard, mouse, touch, Event, Point, Rectangle, Size, Vector};
+use iced_winit::runtime::program::State;
+use iced_winit::{conversion, winit};
+use winit::event::{ElementState, WindowEvent};
+use winit::keyboard::{Key, PhysicalKey};
+
+use crate::gesture::{self, Gesture};
+use crate::ime::{Ime, Preedit};
+use crate::input::Input;
+use crate::{shortcut, AppConfig, Program};
+
+/// The window of the runner, or the harness.
+pub(crate) trait Host<P: Program> {
+    fn state(&mut self) -> &mut State<P>;
+
+    fn scale_factor(&self) -> f64;
+
+    /// Runs the program with the queued events and messages.
+    fn update(&mut self, input: &Input, config: &AppConfig);
+
+    /// Like [`update`](Host::update), for the events made up for the input
+    /// method, which aren't passed to the event listeners of the program.
+    fn update_synthetic(&mut self, input: &Input, config: &AppConfig);
+
+    /// Starts scrolling after a fling.
+    fn fling(&mut self, position: Point, velocity: Vector, now: Instant);
+
+    /// A finger touched the screen, like in the native lists it stops the fling.
+    fn stop_fling(&mut self);
+
+    /// Reads the system light or dark mode again,
+    /// returns whether the theme could change.
+    fn update_appearance(&mut self) -> bool;
+
+    /// Whether a text widget has the focus.
+    fn is_text_focused(&mut self) -> bool;
+
+    /// Where the focused text widget draws its caret, in logical pixels,
+    /// see `ime::find_caret`.
+    fn find_caret(&mut self, input: &Input, config: &AppConfig, near: Point) -> Option<Rectangle>;
+
+    /// Where the candidate window of the input method goes, in logical pixels.
+    fn set_ime_cursor_area(&mut self, position: Point, size: Size);
+
+    /// The composing text of the input method, drawn over the ui from the next update.
+    fn set_preedit(&mut self, preedit: Option<Preedit>);
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Dispatch {
+    pub(crate) input: Input,
+    pub(crate) gestures: gesture::Recognizer,
+    pub(crate) ime: Ime,
+}
+
+impl Dispatch {
+    pub(crate) fn new(gestures: gesture::Config) -> Self {
+        Self {
+            input: Input::default(),
+            gestures: gesture::Recognizer::new(gestures),
+            ime: Ime::default(),
+        }
+    }
+
+    /// Forgets the fingers and keys, e.g. lifted while the app was in background.
+    pub(crate) fn reset(&mut self) {
+        self.input = Input::default();
+        self.gestures.reset();
+    }
+
+    /// Handles an event of the window, returns whether the ui was updated.
+    pub(crate) fn window_event<P: Program>(
+        &mut self,
+        host: &mut impl Host<P>,
+        config: &AppConfig,
+        event: WindowEvent,
+        now: Instant,
+    ) -> bool {
+        self.input.window_event(&event);
+
+        let mut changed = false;
+        match &event {
+            // winit reports every configuration change on android as a scale
+            // factor change, including switching the dark mode
+            WindowEvent::ThemeChanged(_) | WindowEvent::ScaleFactorChanged { .. } => {
+                changed = host.update_appearance();
+            }
+            WindowEvent::Ime(ime) => {
+                return self.compose(host, config, ime);
+            }
+            _ => {}
+        }
+
+        let event = conversion::window_event(event, host.scale_factor(), self.input.modifiers());
+
+        self.dispatch(host, config, event, changed, now)
+    }
+
+    /// A key event made by hand, `winit::event::KeyEvent` can't be created
+    /// outside of winit. Used by the harness.
+    pub(crate) fn key<P: Program>(
+        &mut self,
+        host: &mut impl Host<P>,
+        config: &AppConfig,
+        physical_key: PhysicalKey,
+        key: Key,
+        state: ElementState,
+        now: Instant,
+    ) -> bool {
+        self.input.key(physical_key, &key, state, false);
+
+        let modifiers = conversion::modifiers(self.input.modifiers());
+        let location = keyboard::Location::Standard;
+
+        let event = match state {
+            ElementState::Pressed => {
+                let key = conversion::key(key);
+
+                keyboard::Event::KeyPressed {
+                    key: key.clone(),
+                    modified_key: key,
+                    physical_key: conversion::physical_key(physical_key),
+                    modifiers,
+                    location,
+                    // like winit on android, `shortcut::key_press` takes it from the key
+                    text: None,
+                }
+            }
+            ElementState::Released => keyboard::Event::KeyReleased {
+                key: conversion::key(key),
+                modifiers,
+                location,
+            },
+        };
+
+        self.dispatch(host, config, Some(Event::Keyboard(event)), false, now)
+    }
+
+    /// An event of the input method which doesn't come from winit,
+    /// like the text buffer of GameActivity.
+    #[cfg(all(target_os = "android", feature = "game-activity"))]
+    pub(crate) fn ime<P: Program>(
+        &mut self,
+        host: &mut impl Host<P>,
+        config: &AppConfig,
+        ime: &winit::event::Ime,
+    ) -> bool {
+        self.compose(host, config, ime)
+    }
+
+    /// A long press has no event of its own, returns whether the ui was updated.
+    pub(crate) fn poll<P: Program>(
+        &mut self,
+        host: &mut impl Host<P>,
+        config: &AppConfig,
+        now: Instant,
+    ) -> bool {
+        let Some(gesture) = self.gestures.poll(now) else {
+            return false;
+        };
+
+        gesture_recognized(host, gesture, now);
+        host.update(&self.input, config);
+        true
+    }
+
+    /// Lifts every finger, e.g. when the app goes to background in the middle
+    /// of a gesture, returns whether the ui was updated.
+    pub(crate) fn lose_fingers<P: Program>(
+        &mut self,
+        host: &mut impl Host<P>,
+        config: &AppConfig,
+    ) -> bool {
+        for event in self.input.lose_fingers(host.scale_factor()) {
+            host.state().queue_event(event);
+        }
+        self.gestures.reset();
+
+        if host.state().is_queue_empty() {
+            return false;
+        }
+        host.update(&self.input, config);
+        true
+    }
+
+    // returns whether the ui was updated
+    fn compose<P: Program>(
+        &mut self,
+        host: &mut impl Host<P>,
+        config: &AppConfig,
+        ime: &winit::event::Ime,
+    ) -> bool {
+        let was_composing = self.ime.is_composing();
+        self.locate_caret(host, config);
+
+        let events = self.ime.event(ime);
+        let typing = !events.is_empty();
+        self.type_composition(host, config, events, was_composing);
+
+        // the typed text moved the caret
+        if typing {
+            self.locate_caret(host, config);
+        }
+        if let Some((position, size)) = self.ime.cursor_area(config) {
+            host.set_ime_cursor_area(position, size);
+        }
+        true
+    }
+
+    fn locate_caret<P: Program>(&mut self, host: &mut impl Host<P>, config: &AppConfig) {
+        let focused = host.is_text_focused();
+        let caret = focused
+            .then(|| host.find_caret(&self.input, config, self.ime.near()))
+            .flatten();
+
+        self.ime.located(focused, caret);
+    }
+
+    // the typing isn't passed to the event listeners of the program
+    fn type_composition<P: Program>(
+        &mut self,
+        host: &mut impl Host<P>,
+        config: &AppConfig,
+        events: Vec<Event>,
+        was_composing: bool,
+    ) {
+        let composing = self.ime.is_composing();
+        let typing = !events.is_empty();
+
+        host.set_preedit(self.ime.preedit(config));
+
+        // handled before the typing, so the program knows what it's about
+        if !was_composing && (composing || typing) {
+            if let Some(message) = host.state().program().composing(true) {
+                host.state().queue_message(message);
+                host.update(&self.input, config);
+            }
+        }
+
+        for event in events {
+            host.state().queue_event(event);
+        }
+
+        // after the typing, messages are handled after the events
+        if !composing && (was_composing || typing) {
+            if let Some(message) = host.state().program().composing(false) {
+                host.state().queue_message(message);
+            }
+        }
+
+        // also draws the preedit
+        host.update_synthetic(&self.input, config);
+    }
+
+    fn dispatch<P: Program>(
+        &mut self,
+        host: &mut impl Host<P>,
+        config: &AppConfig,
+        event: Option<Event>,
+        changed: bool,
+        now: Instant,
+    ) -> bool {
+        // before the key event, so the widgets see it with the new modifiers
+        if self.input.take_modifiers_changed() {
+            host.state()
+                .queue_event(Event::Keyboard(keyboard::Event::ModifiersChanged(
+                    conversion::modifiers(self.input.modifiers()),
+                )));
+        }
+
+        if let Some(event) = event {
+            let event = match event {
+                Event::Keyboard(event) => Event::Keyboard(shortcut::key_press(
+                    event,
+                    self.input.caps_lock(),
+                    self.input.fn_key(),
+                )),
+                event => event,
+            };
+
+            if let Event::Keyboard(keyboard) = &event {
+                if let Some(shortcut) = shortcut::shortcut(keyboard) {
+                    log::debug!("Shortcut {shortcut:?}");
+
+                    if let Some(message) = host.state().program().shortcut(shortcut) {
+                        host.state().queue_message(message);
+                    }
+                }
+            }
+
+            // the composition is finished where the caret was
+            if moves_caret(&event) && self.ime.is_composing() {
+                let events = self.ime.interrupt();
+                self.type_composition(host, config, events, true);
+            }
+
+            if let Event::Mouse(mouse::Event::ButtonPressed(_))
+            | Event::Touch(touch::Event::FingerPressed { .. }) = event
+            {
+                if let Some(position) = self.input.cursor(host.scale_factor()).position() {
+                    self.ime.pressed(position);
+                }
+            }
+
+            if let Event::Touch(touch) = event {
+                if let touch::Event::FingerPressed { .. } = touch {
+                    host.stop_fling();
+                }
+
+                if let Some(gesture) = self.gestures.touch(touch, now) {
+                    gesture_recognized(host, gesture, now);
+                }
+            }
+            host.state().queue_event(event);
+        }
+
+        let mut updated = false;
+        if changed || !host.state().is_queue_empty() {
+            host.update(&self.input, config);
+            updated = true;
+        }
+
+        // the lift is handled with the cursor where the finger was,
+        // then it's gone so nothing stays hovered
+        if self.input.take_lifted() {
+            host.state()
+                .queue_event(Event::Mouse(mouse::Event::CursorLeft));
+            host.update(&self.input, config);
+            updated = true;
+        }
+
+        updated
+    }
+}
+
+fn moves_caret(event: &Event) -> bool {
+    match event {
+        Event::Mouse(mouse::Event::ButtonPressed(_))
+        | Event::Touch(touch::Event::FingerPressed { .. }) => true,
+        Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => !matches!(
+            key,
+            keyboard::Key::Named(
+                keyboard::key::Named::Shift
+                    | keyboard::key::Named::Control
+                    | keyboard::key::Named::Alt
+                    | keyboard::key::Named::Super
+            )
+        ),
+        _ => false,
+    }
+}
+
+fn gesture_recognized<P: Program>(host: &mut impl Host<P>, gesture: Gesture, now: Instant) {
+    log::debug!("Gesture {gesture:?}");
+
+    if let Gesture::Fling { position, velocity } = gesture {
+        host.fling(position, velocity, now);
+    }
+
+    if let Some(message) = host.state().program().gesture(gesture) {
+        host.state().queue_message(message);
+    }
+}
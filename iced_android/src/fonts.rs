@@ -0,0 +1,86 @@
+//! Fonts registered at startup, from the assets and the system font directories.
+
+use std::mem;
+use std::sync::{Arc, Once};
+
+use iced_wgpu::graphics::text::{self, cosmic_text, cosmic_text::fontdb};
+
+use crate::{assets, platform, AppConfig};
+
+/// Adds the fonts of the config to the font system shared by all renderers.
+/// It's global, so only the first config with fonts is loaded.
+///
+/// When the default font is missing a glyph, cosmic-text tries the faces with
+/// the weight of the text before the others, and among them the ones added
+/// first. So the faces of the fallback families are added first, after the
+/// faces already in the font system, e.g. the system fonts on desktop.
+pub(crate) fn load(config: &AppConfig) {
+    static LOAD: Once = Once::new();
+
+    if config.fonts.is_empty() && !config.system_fonts {
+        return;
+    }
+
+    // every headless harness loads the config again
+    LOAD.call_once(|| load_once(config));
+}
+
+fn load_once(config: &AppConfig) {
+    let mut fonts = fontdb::Database::new();
+
+    for path in &config.fonts {
+        match assets::read(path) {
+            Ok(bytes) => {
+                let ids = fonts.load_font_source(fontdb::Source::Binary(Arc::new(bytes)));
+                if ids.is_empty() {
+                    log::error!("{path} is not a font");
+                }
+            }
+            Err(error) => log::error!("Failed to load font {path}: {error}"),
+        }
+    }
+
+    if config.system_fonts {
+        for dir in platform::SYSTEM_FONT_DIRS {
+            fonts.load_fonts_dir(dir);
+        }
+    }
+
+    let mut faces: Vec<_> = fonts.faces().cloned().collect();
+    // stable, the assets stay in front of the system fonts
+    faces.sort_by_key(|face| fallback_rank(face, &config.fallback_fonts));
+
+    log::info!("Loaded {} font faces", faces.len());
+
+    let mut font_system = text::font_system().write().expect("Write font system");
+    let empty =
+        cosmic_text::FontSystem::new_with_locale_and_db(String::new(), fontdb::Database::new());
+    let (locale, mut db) = mem::replace(font_system.raw(), empty).into_locale_and_db();
+
+    for face in faces {
+        db.push_face_info(face);
+    }
+
+    if config.system_fonts {
+        if let Some(family) = platform::SANS_SERIF_FAMILY {
+            db.set_sans_serif_family(family);
+        }
+        if let Some(family) = platform::MONOSPACE_FAMILY {
+            db.set_monospace_family(family);
+        }
+    }
+
+    // the monospace faces are only collected when the font system is created
+    *font_system.raw() = cosmic_text::FontSystem::new_with_locale_and_db(locale, db);
+}
+
+fn fallback_rank(face: &fontdb::FaceInfo, fallback_fonts: &[String]) -> usize {
+    fallback_fonts
+        .iter()
+        .position(|fallback| {
+            face.families
+                .iter()
+                .any(|(family, _)| family.eq_ignore_ascii_case(fallback))
+        })
+        .unwrap_or(fallback_fonts.len())
+}
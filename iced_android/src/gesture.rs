@@ -0,0 +1,304 @@
+//! Taps, long presses, pinches and flings, recognized from the touch events.
+//!
+//! The runner passes them to [`Program::gesture`](crate::Program::gesture).
+//! Custom widgets can run their own [`Recognizer`] on the touch events they get.
+
+use std::time::{Duration, Instant};
+
+use iced_winit::core::{touch, Point, Vector};
+
+/// A gesture of one or two fingers, positions are in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A finger touched and lifted without moving.
+    Tap { position: Point },
+    /// A second tap close to the previous one, reported instead of its [`Gesture::Tap`].
+    DoubleTap { position: Point },
+    /// A finger stayed down without moving for the long press timeout.
+    /// No tap is reported when it's lifted.
+    LongPress { position: Point },
+    /// Two fingers moved apart or together, `scale` is relative to the previous pinch.
+    Pinch { center: Point, scale: f32 },
+    /// A finger was lifted while moving fast, `velocity` is in logical pixels per second.
+    Fling { position: Point, velocity: Vector },
+}
+
+/// Thresholds of the recognizer, distances are in logical pixels.
+///
+/// On android they are read from `ViewConfiguration`, the defaults are
+/// the ones of the platform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    /// How far a finger can move before a tap or a long press becomes a drag.
+    pub touch_slop: f32,
+    /// Max distance between the two taps of a double tap.
+    pub double_tap_slop: f32,
+    /// Max time between the first lift and the second touch of a double tap.
+    pub double_tap_timeout: Duration,
+    pub long_press_timeout: Duration,
+    /// In logical pixels per second, slower lifts aren't flings.
+    pub min_fling_velocity: f32,
+    /// Faster flings are slowed down to this.
+    pub max_fling_velocity: f32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            touch_slop: 8.0,
+            double_tap_slop: 100.0,
+            double_tap_timeout: Duration::from_millis(300),
+            long_press_timeout: Duration::from_millis(400),
+            min_fling_velocity: 50.0,
+            max_fling_velocity: 8000.0,
+        }
+    }
+}
+
+// only the movement of the last moments counts for the fling velocity
+const VELOCITY_WINDOW: Duration = Duration::from_millis(100);
+
+/// Turns the touch events into [`Gesture`]s.
+///
+/// Long presses have no touch event to be reported on, call [`Recognizer::poll`]
+/// when [`Recognizer::deadline`] is reached.
+#[derive(Debug)]
+pub struct Recognizer {
+    config: Config,
+    fingers: Vec<(touch::Finger, Point)>,
+    // the only finger of a gesture, until another one touches
+    single: Option<Single>,
+    // span between the first two fingers
+    pinch: Option<Pinch>,
+    // for double taps
+    last_tap: Option<(Point, Instant)>,
+}
+
+#[derive(Debug)]
+struct Single {
+    id: touch::Finger,
+    start: Point,
+    started_at: Instant,
+    // beyond the touch slop, it's not a tap or a long press anymore
+    moved: bool,
+    long_pressed: bool,
+    samples: Vec<(Instant, Point)>,
+}
+
+#[derive(Debug)]
+struct Pinch {
+    span: f32,
+    // the span has to change by the touch slop first, resting fingers jitter
+    started: bool,
+}
+
+impl Recognizer {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            fingers: Vec::new(),
+            single: None,
+            pinch: None,
+            last_tap: None,
+        }
+    }
+
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    /// Forgets the fingers on the screen, e.g. when the app goes to background.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config);
+    }
+
+    pub fn touch(&mut self, event: touch::Event, now: Instant) -> Option<Gesture> {
+        match event {
+            touch::Event::FingerPressed { id, position } => {
+                self.fingers.retain(|(finger, _)| *finger != id);
+                self.fingers.push((id, position));
+
+                match self.fingers.len() {
+                    1 => {
+                        self.single = Some(Single {
+                            id,
+                            start: position,
+                            started_at: now,
+                            moved: false,
+                            long_pressed: false,
+                            samples: vec![(now, position)],
+                        });
+                    }
+                    2 => {
+                        // the rest of the gesture is a pinch, not a tap or a fling
+                        self.single = None;
+                        self.pinch = Some(Pinch {
+                            span: self.span(),
+                            started: false,
+                        });
+                    }
+                    _ => {}
+                }
+
+                None
+            }
+            touch::Event::FingerMoved { id, position } => {
+                let index = self.fingers.iter().position(|(finger, _)| *finger == id)?;
+                self.fingers[index].1 = position;
+
+                if let Some(single) = self.single.as_mut().filter(|single| single.id == id) {
+                    single.moved |= single.start.distance(position) > self.config.touch_slop;
+                    single.push_sample(now, position);
+                    return None;
+                }
+
+                // only the first two fingers pinch
+                if index > 1 {
+                    return None;
+                }
+
+                let span = self.span();
+                let touch_slop = self.config.touch_slop;
+                let pinch = self.pinch.as_mut()?;
+
+                if !pinch.started {
+                    pinch.started = (span - pinch.span).abs() > touch_slop;
+                    if !pinch.started {
+                        return None;
+                    }
+                }
+
+                let scale = span / pinch.span;
+                pinch.span = span;
+
+                (scale.is_finite() && scale > 0.0).then(|| Gesture::Pinch {
+                    center: self.center(),
+                    scale,
+                })
+            }
+            touch::Event::FingerLifted { id, position } => {
+                self.remove(id);
+
+                let single = self.single.take().filter(|single| single.id == id)?;
+                self.lifted(single, position, now)
+            }
+            touch::Event::FingerLost { id, .. } => {
+                self.remove(id);
+                self.single = self.single.take().filter(|single| single.id != id);
+
+                None
+            }
+        }
+    }
+
+    /// When the finger on the screen becomes a long press, if it doesn't move.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.single
+            .as_ref()
+            .filter(|single| !single.moved && !single.long_pressed)
+            .map(|single| single.started_at + self.config.long_press_timeout)
+    }
+
+    /// Reports the long press once the [`deadline`](Recognizer::deadline) is reached.
+    pub fn poll(&mut self, now: Instant) -> Option<Gesture> {
+        if now < self.deadline()? {
+            return None;
+        }
+
+        let single = self.single.as_mut()?;
+        single.long_pressed = true;
+        // a tap after it isn't the second one of a double tap
+        self.last_tap = None;
+
+        Some(Gesture::LongPress {
+            position: single.samples.last().map_or(single.start, |(_, p)| *p),
+        })
+    }
+
+    fn lifted(&mut self, mut single: Single, position: Point, now: Instant) -> Option<Gesture> {
+        if single.long_pressed {
+            return None;
+        }
+
+        if single.moved {
+            single.push_sample(now, position);
+            let velocity = single.velocity()?;
+            let speed = velocity.x.hypot(velocity.y);
+
+            if speed < self.config.min_fling_velocity {
+                return None;
+            }
+
+            let velocity = if speed > self.config.max_fling_velocity {
+                velocity * (self.config.max_fling_velocity / speed)
+            } else {
+                velocity
+            };
+
+            return Some(Gesture::Fling { position, velocity });
+        }
+
+        let double_tap = self.last_tap.take().is_some_and(|(last, lifted_at)| {
+            single.started_at.saturating_duration_since(lifted_at) <= self.config.double_tap_timeout
+                && last.distance(position) <= self.config.double_tap_slop
+        });
+
+        if double_tap {
+            Some(Gesture::DoubleTap { position })
+        } else {
+            self.last_tap = Some((position, now));
+            Some(Gesture::Tap { position })
+        }
+    }
+
+    fn remove(&mut self, id: touch::Finger) {
+        let Some(index) = self.fingers.iter().position(|(finger, _)| *finger == id) else {
+            return;
+        };
+        self.fingers.remove(index);
+
+        // the pinch is over when one of its fingers is lifted
+        if index < 2 {
+            self.pinch = None;
+        }
+    }
+
+    fn span(&self) -> f32 {
+        match &self.fingers[..] {
+            [(_, a), (_, b), ..] => a.distance(*b),
+            _ => 0.0,
+        }
+    }
+
+    fn center(&self) -> Point {
+        match &self.fingers[..] {
+            [(_, a), (_, b), ..] => Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0),
+            _ => Point::ORIGIN,
+        }
+    }
+}
+
+impl Default for Recognizer {
+    fn default() -> Self {
+        Self::new(Config::default())
+    }
+}
+
+impl Single {
+    fn push_sample(&mut self, now: Instant, position: Point) {
+        self.samples.push((now, position));
+        self.samples
+            .retain(|(time, _)| now.saturating_duration_since(*time) <= VELOCITY_WINDOW);
+    }
+
+    // between the oldest and the newest sample of the window
+    fn velocity(&self) -> Option<Vector> {
+        let (first_time, first) = self.samples.first()?;
+        let (last_time, last) = self.samples.last()?;
+        let seconds = last_time
+            .saturating_duration_since(*first_time)
+            .as_secs_f32();
+
+        (seconds > 0.0).then(|| (*last - *first) * (1.0 / seconds))
+    }
+}
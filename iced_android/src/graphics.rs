@@ -0,0 +1,231 @@
+mod gpu;
+mod overlay;
+mod pipeline_cache;
+pub(crate) mod software;
+
+use std::fmt;
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use iced_wgpu::graphics::compositor::SurfaceError;
+use iced_wgpu::graphics::Viewport;
+use iced_wgpu::wgpu;
+use iced_widget::Renderer;
+use iced_winit::core::Color;
+use iced_winit::winit::event_loop::EventLoopProxy;
+use iced_winit::winit::window::Window;
+
+use crate::config::Rendering;
+use crate::scene::CustomScene;
+use crate::screenshot::Screenshot;
+use crate::{AppConfig, UserEvent};
+use gpu::{Gpu, Init};
+use software::Software;
+
+/// Everything needed to draw into the window.
+///
+/// Kept across suspend/resume, only the surface is recreated.
+/// Rebuilt from scratch when the device is in a bad state.
+pub(crate) enum Graphics<S> {
+    Gpu(Box<Gpu<S>>),
+    // tiny-skia + softbuffer, for devices without a usable gpu
+    Software(Software),
+}
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    CreateSurface(wgpu::CreateSurfaceError),
+    NoAdapter,
+    RequestDevice(wgpu::RequestDeviceError),
+    NoSurfaceFormat,
+    // a new surface, e.g. after resume, which can't use the format of the pipelines
+    UnsupportedSurfaceFormat(wgpu::TextureFormat),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::CreateSurface(e) => write!(f, "failed to create surface: {e}"),
+            Error::NoAdapter => write!(f, "no suitable adapter found"),
+            Error::RequestDevice(e) => write!(f, "failed to request device: {e}"),
+            Error::NoSurfaceFormat => write!(f, "surface doesn't support any format"),
+            Error::UnsupportedSurfaceFormat(format) => {
+                write!(f, "surface doesn't support the format {format:?}")
+            }
+        }
+    }
+}
+
+/// Gpu initialization running on its own thread, so a slow driver doesn't block
+/// the event loop. [`UserEvent::GraphicsReady`] is sent when it's done.
+pub(crate) struct Pending<S> {
+    receiver: mpsc::Receiver<Result<Init<S>, Error>>,
+}
+
+impl<S: CustomScene> Pending<S> {
+    /// `background_color` is presented as soon as the device is created.
+    pub(crate) fn start(
+        config: &AppConfig,
+        window: Arc<Window>,
+        background_color: Color,
+        proxy: EventLoopProxy<UserEvent>,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let config = config.clone();
+
+        thread::Builder::new()
+            .name("gpu init".to_owned())
+            .spawn(move || {
+                let _notify = Notify(proxy);
+                let _ = sender.send(gpu::init(&config, window, Some(background_color)));
+            })
+            .expect("Should spawn gpu init thread");
+
+        Self { receiver }
+    }
+
+    /// `None` while the thread is still running.
+    pub(crate) fn finish(
+        &self,
+        config: &AppConfig,
+        window: Arc<Window>,
+    ) -> Option<Result<Graphics<S>, Error>> {
+        let init = match self.receiver.try_recv() {
+            Ok(init) => init,
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => panic!("Gpu init thread panicked"),
+        };
+
+        Some(Graphics::from_init(config, window, init))
+    }
+
+    /// Blocks until the thread is done, e.g. before the native window it uses
+    /// is destroyed. A failure is returned without the software fallback, it's
+    /// likely caused by the window going away, so the init is retried on resume.
+    pub(crate) fn wait(
+        &self,
+        config: &AppConfig,
+        window: Arc<Window>,
+    ) -> Result<Graphics<S>, Error> {
+        let init = self.receiver.recv().expect("Gpu init thread panicked")?;
+
+        Graphics::from_init(config, window, Ok(init))
+    }
+}
+
+// wakes the event loop up, also when the thread panics
+struct Notify(EventLoopProxy<UserEvent>);
+
+impl Drop for Notify {
+    fn drop(&mut self) {
+        let _ = self.0.send_event(UserEvent::GraphicsReady);
+    }
+}
+
+impl<S: CustomScene> Graphics<S> {
+    fn from_init(
+        config: &AppConfig,
+        window: Arc<Window>,
+        init: Result<Init<S>, Error>,
+    ) -> Result<Self, Error> {
+        match (init, config.rendering) {
+            (Ok(init), _) => Ok(Graphics::Gpu(Box::new(Gpu::new(init)))),
+            (Err(error), Rendering::Auto) => {
+                log::warn!("Can't use the gpu: {error}, falling back to software rendering");
+                Ok(Graphics::software(config, window))
+            }
+            (Err(error), _) => Err(error),
+        }
+    }
+
+    /// Unlike the gpu, tiny-skia can't fail to initialize.
+    pub(crate) fn software(config: &AppConfig, window: Arc<Window>) -> Self {
+        Graphics::Software(Software::new(config, window))
+    }
+
+    pub(crate) fn create_renderer(&self, config: &AppConfig) -> Renderer {
+        match self {
+            Graphics::Gpu(gpu) => gpu.create_renderer(config),
+            Graphics::Software(software) => software.create_renderer(),
+        }
+    }
+
+    pub(crate) fn has_surface(&self) -> bool {
+        match self {
+            Graphics::Gpu(gpu) => gpu.has_surface(),
+            Graphics::Software(software) => software.has_surface(),
+        }
+    }
+
+    pub(crate) fn suspend(&mut self) {
+        match self {
+            Graphics::Gpu(gpu) => gpu.suspend(),
+            Graphics::Software(software) => software.suspend(),
+        }
+    }
+
+    pub(crate) fn resume(&mut self, window: Arc<Window>) -> Result<(), Error> {
+        match self {
+            Graphics::Gpu(gpu) => gpu.resume(window),
+            Graphics::Software(software) => {
+                software.resume(window);
+                Ok(())
+            }
+        }
+    }
+
+    /// Configures the surface again, also used to recover a lost surface.
+    pub(crate) fn resize(&mut self, viewport: &Viewport) {
+        match self {
+            Graphics::Gpu(gpu) => gpu.resize(viewport),
+            Graphics::Software(software) => software.resize(viewport),
+        }
+    }
+
+    pub(crate) fn reload_scene(&mut self) {
+        match self {
+            Graphics::Gpu(gpu) => gpu.reload_scene(),
+            Graphics::Software(_) => log::info!("No scene with software rendering"),
+        }
+    }
+
+    /// `None` with software rendering.
+    pub(crate) fn scene_mut(&mut self) -> Option<&mut S> {
+        match self {
+            Graphics::Gpu(gpu) => Some(gpu.scene_mut()),
+            Graphics::Software(_) => None,
+        }
+    }
+
+    pub(crate) fn present(
+        &mut self,
+        renderer: &mut Renderer,
+        viewport: &Viewport,
+        background_color: Color,
+    ) -> Result<(), SurfaceError> {
+        match self {
+            Graphics::Gpu(gpu) => gpu.present(renderer, viewport, background_color),
+            Graphics::Software(software) => software.present(renderer, viewport, background_color),
+        }
+    }
+
+    /// Renders the last frame offscreen, the surface is not needed.
+    ///
+    /// The custom scene can only be drawn with the gpu.
+    pub(crate) fn screenshot(
+        &mut self,
+        renderer: &mut Renderer,
+        viewport: &Viewport,
+        background_color: Color,
+        include_scene: bool,
+    ) -> Option<Screenshot> {
+        match self {
+            Graphics::Gpu(gpu) => {
+                gpu.screenshot(renderer, viewport, background_color, include_scene)
+            }
+            Graphics::Software(software) => {
+                software.screenshot(renderer, viewport, background_color)
+            }
+        }
+    }
+}
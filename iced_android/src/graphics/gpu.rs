@@ -0,0 +1,493 @@
+use std::sync::{mpsc, Arc};
+use std::time::Instant;
+
+use iced_wgpu::graphics::compositor::SurfaceError;
+use iced_wgpu::graphics::Viewport;
+use iced_wgpu::{wgpu, Engine};
+use iced_widget::Renderer;
+use iced_winit::core::{Color, Size};
+use iced_winit::winit::window::Window;
+use wgpu::{Adapter, Device, Instance, Queue};
+
+use super::overlay::ErrorOverlay;
+use super::pipeline_cache::PipelineCache;
+use super::Error;
+use crate::scene::{self, CustomScene};
+use crate::screenshot::{self, Screenshot};
+use crate::AppConfig;
+
+/// Everything tied to the gpu device, created from [`Init`].
+pub(crate) struct Gpu<S> {
+    instance: Instance,
+    adapter: Adapter,
+    device: Device,
+    queue: Queue,
+    // dropped while the app is suspended
+    surface: Option<wgpu::Surface<'static>>,
+    surface_config: wgpu::SurfaceConfiguration,
+    engine: Engine,
+    // `None` if the backend doesn't support it
+    pipeline_cache: Option<PipelineCache>,
+    scene: S,
+    // created when the scene reports an error for the first time
+    error_overlay: Option<ErrorOverlay>,
+}
+
+/// The device, the surface and the pipelines, which can be created on another thread.
+///
+/// The ui renderers of [`Gpu`] can't be sent between threads, they are created
+/// on the event loop thread.
+pub(crate) struct Init<S> {
+    instance: Instance,
+    adapter: Adapter,
+    device: Device,
+    queue: Queue,
+    surface: wgpu::Surface<'static>,
+    surface_config: wgpu::SurfaceConfiguration,
+    engine: Engine,
+    pipeline_cache: Option<PipelineCache>,
+    scene: S,
+}
+
+/// Creates the surface, the device and the pipelines, logging how long each step took.
+///
+/// With a `placeholder` color, a frame cleared with it is presented as soon as
+/// the device is ready, so there is something on screen while the pipelines compile.
+pub(crate) fn init<S: CustomScene>(
+    config: &AppConfig,
+    window: Arc<Window>,
+    placeholder: Option<Color>,
+) -> Result<Init<S>, Error> {
+    let mut stopwatch = Stopwatch::new();
+
+    let instance = Instance::new(wgpu::InstanceDescriptor {
+        backends: config.backends,
+        ..Default::default()
+    });
+
+    let physical_size = window.inner_size();
+    let scale_factor = window.scale_factor();
+
+    let surface = instance
+        .create_surface(window)
+        .map_err(Error::CreateSurface)?;
+    stopwatch.lap("surface");
+
+    let (format, capabilities, adapter, device, queue) = futures::executor::block_on(async {
+        let adapter = wgpu::util::initialize_adapter_from_env_or_default(&instance, Some(&surface))
+            .await
+            .ok_or(Error::NoAdapter)?;
+        stopwatch.lap("adapter");
+
+        let adapter_features = adapter.features();
+
+        let capabilities = surface.get_capabilities(&adapter);
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    required_features: adapter_features
+                        & (wgpu::Features::default() | wgpu::Features::PIPELINE_CACHE),
+                    required_limits: wgpu::Limits::default(),
+                    memory_hints: wgpu::MemoryHints::MemoryUsage,
+                },
+                None,
+            )
+            .await
+            .map_err(Error::RequestDevice)?;
+        stopwatch.lap("device");
+
+        Ok((
+            capabilities
+                .formats
+                .iter()
+                .copied()
+                .find(wgpu::TextureFormat::is_srgb)
+                .or_else(|| capabilities.formats.first().copied())
+                .ok_or(Error::NoSurfaceFormat)?,
+            capabilities,
+            adapter,
+            device,
+            queue,
+        ))
+    })?;
+
+    log::info!("Using adapter {:?}", adapter.get_info());
+
+    let surface_config = config.surface_configuration(
+        &capabilities,
+        format,
+        physical_size.width,
+        physical_size.height,
+    );
+    surface.configure(&device, &surface_config);
+
+    if let Some(color) = placeholder {
+        present_placeholder(&device, &queue, &surface, color);
+        stopwatch.lap("placeholder frame");
+    }
+
+    let pipeline_cache = PipelineCache::load(&adapter, &device);
+    stopwatch.lap("pipeline cache");
+
+    let engine = Engine::new(&adapter, &device, &queue, format, None);
+    stopwatch.lap("engine");
+
+    let mut scene = S::new(
+        &device,
+        format,
+        pipeline_cache.as_ref().map(PipelineCache::raw),
+    );
+    scene.resize(&Viewport::with_physical_size(
+        Size::new(physical_size.width, physical_size.height),
+        scale_factor,
+    ));
+    stopwatch.lap("scene");
+
+    log::info!("Gpu init: total {:?}", stopwatch.started.elapsed());
+
+    Ok(Init {
+        instance,
+        adapter,
+        device,
+        queue,
+        surface,
+        surface_config,
+        engine,
+        pipeline_cache,
+        scene,
+    })
+}
+
+// only the background color, the program isn't updated before the first real frame
+fn present_placeholder(device: &Device, queue: &Queue, surface: &wgpu::Surface, color: Color) {
+    let frame = match surface.get_current_texture() {
+        Ok(frame) => frame,
+        Err(error) => {
+            log::warn!("Skipping the placeholder frame: {error}");
+            return;
+        }
+    };
+
+    let view = frame
+        .texture
+        .create_view(&wgpu::TextureViewDescriptor::default());
+    let mut encoder =
+        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    // the pass only clears
+    drop(scene::clear(&view, &mut encoder, color));
+
+    queue.submit([encoder.finish()]);
+    frame.present();
+}
+
+// logs the duration of every step, to spot a slower startup
+struct Stopwatch {
+    started: Instant,
+    lap: Instant,
+}
+
+impl Stopwatch {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            lap: now,
+        }
+    }
+
+    fn lap(&mut self, step: &str) {
+        let now = Instant::now();
+        log::info!("Gpu init: {step} {:?}", now - self.lap);
+        self.lap = now;
+    }
+}
+
+impl<S: CustomScene> Gpu<S> {
+    pub(crate) fn new(init: Init<S>) -> Self {
+        let Init {
+            instance,
+            adapter,
+            device,
+            queue,
+            surface,
+            surface_config,
+            engine,
+            pipeline_cache,
+            scene,
+        } = init;
+
+        Self {
+            instance,
+            adapter,
+            device,
+            queue,
+            surface: Some(surface),
+            surface_config,
+            engine,
+            pipeline_cache,
+            scene,
+            error_overlay: None,
+        }
+    }
+
+    pub(crate) fn create_renderer(&self, config: &AppConfig) -> Renderer {
+        Renderer::Primary(iced_wgpu::Renderer::new(
+            &self.device,
+            &self.engine,
+            config.default_font,
+            config.default_text_size,
+        ))
+    }
+
+    pub(crate) fn has_surface(&self) -> bool {
+        self.surface.is_some()
+    }
+
+    // the process can be killed any time in background
+    pub(crate) fn suspend(&mut self) {
+        self.surface = None;
+
+        if let Some(pipeline_cache) = &self.pipeline_cache {
+            pipeline_cache.save();
+        }
+    }
+
+    pub(crate) fn resume(&mut self, window: Arc<Window>) -> Result<(), Error> {
+        let size = window.inner_size();
+        let viewport =
+            Viewport::with_physical_size(Size::new(size.width, size.height), window.scale_factor());
+
+        let surface = self
+            .instance
+            .create_surface(window)
+            .map_err(Error::CreateSurface)?;
+
+        // the pipelines of iced and the scene are built for the format,
+        // the graphics have to be rebuilt when it's gone
+        let capabilities = surface.get_capabilities(&self.adapter);
+        if !capabilities.formats.contains(&self.surface_config.format) {
+            log::warn!("Supported surface formats: {:?}", capabilities.formats);
+            return Err(Error::UnsupportedSurfaceFormat(self.surface_config.format));
+        }
+
+        self.surface = Some(surface);
+        self.resize(&viewport);
+
+        Ok(())
+    }
+
+    pub(crate) fn resize(&mut self, viewport: &Viewport) {
+        let size = viewport.physical_size();
+        self.surface_config.width = size.width;
+        self.surface_config.height = size.height;
+
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.surface_config);
+        }
+
+        self.scene.resize(viewport);
+    }
+
+    pub(crate) fn reload_scene(&mut self) {
+        self.scene.reload(
+            &self.device,
+            self.pipeline_cache.as_ref().map(PipelineCache::raw),
+        );
+    }
+
+    pub(crate) fn scene_mut(&mut self) -> &mut S {
+        &mut self.scene
+    }
+
+    pub(crate) fn present(
+        &mut self,
+        renderer: &mut Renderer,
+        viewport: &Viewport,
+        background_color: Color,
+    ) -> Result<(), SurfaceError> {
+        let Some(surface) = &self.surface else {
+            return Ok(());
+        };
+
+        let frame = surface.get_current_texture().map_err(|error| match error {
+            wgpu::SurfaceError::Timeout => SurfaceError::Timeout,
+            wgpu::SurfaceError::Outdated => SurfaceError::Outdated,
+            wgpu::SurfaceError::Lost => SurfaceError::Lost,
+            wgpu::SurfaceError::OutOfMemory => SurfaceError::OutOfMemory,
+        })?;
+
+        self.scene.prepare(&self.queue);
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        let view = frame
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        {
+            let mut render_pass = scene::clear(&view, &mut encoder, background_color);
+            self.scene.draw(&mut render_pass);
+        }
+
+        let Renderer::Primary(renderer) = renderer else {
+            unreachable!("gpu renderer is created by Gpu::create_renderer");
+        };
+
+        renderer.present::<String>(
+            &mut self.engine,
+            &self.device,
+            &self.queue,
+            &mut encoder,
+            None,
+            frame.texture.format(),
+            &view,
+            viewport,
+            &[],
+        );
+
+        if let Some(error) = self.scene.error() {
+            self.error_overlay
+                .get_or_insert_with(|| ErrorOverlay::new(&self.device, &self.engine))
+                .present(
+                    &mut self.engine,
+                    &self.device,
+                    &self.queue,
+                    &mut encoder,
+                    frame.texture.format(),
+                    &view,
+                    viewport,
+                    error,
+                );
+        }
+
+        self.engine.submit(&self.queue, encoder);
+        frame.present();
+
+        Ok(())
+    }
+
+    pub(crate) fn screenshot(
+        &mut self,
+        renderer: &mut Renderer,
+        viewport: &Viewport,
+        background_color: Color,
+        include_scene: bool,
+    ) -> Option<Screenshot> {
+        let format = self.surface_config.format;
+        let bgra = match format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+            _ => {
+                log::error!("Screenshots of {format:?} surfaces are not supported");
+                return None;
+            }
+        };
+
+        let Renderer::Primary(renderer) = renderer else {
+            unreachable!("gpu renderer is created by Gpu::create_renderer");
+        };
+
+        let size = viewport.physical_size();
+        if size.width == 0 || size.height == 0 {
+            log::warn!("Can't take a screenshot of an empty viewport");
+            return None;
+        }
+        let extent = wgpu::Extent3d {
+            width: size.width,
+            height: size.height,
+            depth_or_array_layers: 1,
+        };
+
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("iced_android screenshot"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        {
+            let mut render_pass = scene::clear(&view, &mut encoder, background_color);
+            if include_scene {
+                self.scene.draw(&mut render_pass);
+            }
+        }
+
+        renderer.present::<String>(
+            &mut self.engine,
+            &self.device,
+            &self.queue,
+            &mut encoder,
+            None,
+            format,
+            &view,
+            viewport,
+            &[],
+        );
+
+        // rows of the copy have to be aligned
+        let row_size = size.width * 4;
+        let padded_row_size = row_size.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("iced_android screenshot"),
+            size: u64::from(padded_row_size) * u64::from(size.height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_size),
+                    rows_per_image: None,
+                },
+            },
+            extent,
+        );
+
+        self.engine.submit(&self.queue, encoder);
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        let _ = self.device.poll(wgpu::Maintain::Wait);
+
+        if let Err(error) = receiver.recv().expect("Buffer is mapped after poll") {
+            log::error!("Failed to read the screenshot: {error}");
+            return None;
+        }
+
+        let mut pixels = Vec::with_capacity(row_size as usize * size.height as usize);
+        for row in slice
+            .get_mapped_range()
+            .chunks_exact(padded_row_size as usize)
+        {
+            pixels.extend_from_slice(&row[..row_size as usize]);
+        }
+        buffer.unmap();
+
+        if bgra {
+            screenshot::bgra_to_rgba(&mut pixels);
+        }
+
+        Some(Screenshot::new(size.width, size.height, pixels))
+    }
+}
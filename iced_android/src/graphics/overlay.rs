@@ -0,0 +1,104 @@
+use iced_wgpu::graphics::Viewport;
+use iced_wgpu::{wgpu, Engine};
+use iced_widget::{container, text};
+use iced_winit::core::{
+    mouse, renderer, Color, Element, Font, Length, Padding, Pixels, Size, Theme,
+};
+use iced_winit::runtime::user_interface::{self, UserInterface};
+
+/// Error message drawn over the ui.
+///
+/// Has its own renderer, so it's shown whatever the program draws.
+pub(crate) struct ErrorOverlay {
+    renderer: iced_wgpu::Renderer,
+    // the ui is only built again when these change
+    drawn: Option<(String, Size<u32>)>,
+}
+
+impl ErrorOverlay {
+    pub(crate) fn new(device: &wgpu::Device, engine: &Engine) -> Self {
+        Self {
+            renderer: iced_wgpu::Renderer::new(device, engine, Font::MONOSPACE, Pixels(14.0)),
+            drawn: None,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn present(
+        &mut self,
+        engine: &mut Engine,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        format: wgpu::TextureFormat,
+        view: &wgpu::TextureView,
+        viewport: &Viewport,
+        message: &str,
+    ) {
+        let size = viewport.physical_size();
+        let unchanged = self
+            .drawn
+            .as_ref()
+            .is_some_and(|(drawn_message, drawn_size)| {
+                drawn_message == message && *drawn_size == size
+            });
+
+        if !unchanged {
+            self.draw(message, viewport);
+            self.drawn = Some((message.to_owned(), size));
+        }
+
+        self.renderer.present::<String>(
+            engine,
+            device,
+            queue,
+            encoder,
+            None,
+            format,
+            view,
+            viewport,
+            &[],
+        );
+    }
+
+    fn draw(&mut self, message: &str, viewport: &Viewport) {
+        let content: Element<'_, (), Theme, iced_wgpu::Renderer> =
+            container(text(message).font(Font::MONOSPACE).size(14))
+                .width(Length::Fill)
+                // below the status bar
+                .padding(Padding {
+                    top: 40.0,
+                    right: 10.0,
+                    bottom: 10.0,
+                    left: 10.0,
+                })
+                .style(|_theme| container::Style {
+                    text_color: Some(Color::from_rgb(1.0, 0.45, 0.45)),
+                    background: Some(
+                        Color {
+                            a: 0.85,
+                            ..Color::BLACK
+                        }
+                        .into(),
+                    ),
+                    ..container::Style::default()
+                })
+                .into();
+
+        let mut user_interface = UserInterface::build(
+            content,
+            viewport.logical_size(),
+            user_interface::Cache::default(),
+            &mut self.renderer,
+        );
+
+        let _ = user_interface.draw(
+            &mut self.renderer,
+            &Theme::Dark,
+            &renderer::Style {
+                text_color: Color::WHITE,
+            },
+            mouse::Cursor::Unavailable,
+        );
+    }
+}
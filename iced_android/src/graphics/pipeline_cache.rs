@@ -0,0 +1,89 @@
+//! Compiled pipelines kept across launches, in the cache directory of the app.
+//!
+//! Only the pipelines of the [`CustomScene`](crate::CustomScene) use it,
+//! iced_wgpu creates its own without a cache.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use iced_wgpu::wgpu;
+
+use crate::platform;
+
+pub(crate) struct PipelineCache {
+    raw: wgpu::PipelineCache,
+    path: PathBuf,
+}
+
+impl PipelineCache {
+    /// `None` if the backend has no application managed caches (only Vulkan has),
+    /// or there is no cache directory.
+    pub(crate) fn load(adapter: &wgpu::Adapter, device: &wgpu::Device) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::PIPELINE_CACHE) {
+            return None;
+        }
+
+        // vendor and device, so a file of another gpu is never read
+        let key = wgpu::util::pipeline_cache_key(&adapter.get_info())?;
+        let dir = platform::cache_dir()?.join("pipelines");
+        let path = dir.join(&key);
+
+        remove_stale(&dir, &key);
+
+        let data = fs::read(&path).ok();
+        log::info!(
+            "Pipeline cache {}, {} bytes",
+            path.display(),
+            data.as_ref().map_or(0, Vec::len)
+        );
+
+        // SAFETY: the data was written by `save` for the same key, wgpu checks
+        // the driver version in its header, after a driver update it's ignored
+        // and the cache starts empty
+        let raw = unsafe {
+            device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                label: Some("iced_android pipeline cache"),
+                data: data.as_deref(),
+                fallback: true,
+            })
+        };
+
+        Some(Self { raw, path })
+    }
+
+    pub(crate) fn raw(&self) -> &wgpu::PipelineCache {
+        &self.raw
+    }
+
+    /// Writes the cache to a temporary file first, so a killed process
+    /// doesn't leave a truncated one behind.
+    pub(crate) fn save(&self) {
+        let Some(data) = self.raw.get_data() else {
+            return;
+        };
+
+        let temp = self.path.with_extension("temp");
+        let result = fs::create_dir_all(self.path.parent().expect("Cache file is in a directory"))
+            .and_then(|()| fs::write(&temp, &data))
+            .and_then(|()| fs::rename(&temp, &self.path));
+
+        match result {
+            Ok(()) => log::info!("Saved pipeline cache, {} bytes", data.len()),
+            Err(error) => log::error!("Failed to save pipeline cache: {error}"),
+        }
+    }
+}
+
+// caches of a previous gpu, e.g. an external one on desktop
+fn remove_stale(dir: &Path, key: &str) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        if entry.file_name() != key {
+            log::info!("Removing stale pipeline cache {}", entry.path().display());
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use iced_tiny_skia::window::{compositor, Compositor, Surface};
+use iced_wgpu::graphics::compositor::SurfaceError;
+use iced_wgpu::graphics::{Compositor as _, Viewport};
+use iced_widget::Renderer;
+use iced_winit::core::{Color, Rectangle, Size};
+use iced_winit::winit::window::Window;
+
+use crate::screenshot::{self, Screenshot};
+use crate::AppConfig;
+
+/// Draws the ui on the cpu and copies the pixels into the window.
+///
+/// The custom wgpu scene is not drawn, only the background color.
+pub(crate) struct Software {
+    compositor: Compositor,
+    // dropped while the app is suspended
+    surface: Option<Surface>,
+    width: u32,
+    height: u32,
+}
+
+impl Software {
+    pub(crate) fn new(config: &AppConfig, window: Arc<Window>) -> Self {
+        log::info!("Using software rendering");
+
+        let settings = iced_tiny_skia::Settings {
+            default_font: config.default_font,
+            default_text_size: config.default_text_size,
+        };
+        let compositor = compositor::new(settings, window.clone());
+
+        let mut software = Self {
+            compositor,
+            surface: None,
+            width: 0,
+            height: 0,
+        };
+        software.resume(window);
+
+        software
+    }
+
+    pub(crate) fn create_renderer(&self) -> Renderer {
+        Renderer::Secondary(self.compositor.create_renderer())
+    }
+
+    pub(crate) fn has_surface(&self) -> bool {
+        self.surface.is_some()
+    }
+
+    pub(crate) fn suspend(&mut self) {
+        self.surface = None;
+    }
+
+    pub(crate) fn resume(&mut self, window: Arc<Window>) {
+        let size = window.inner_size();
+        let viewport =
+            Viewport::with_physical_size(Size::new(size.width, size.height), window.scale_factor());
+
+        // softbuffer can't have a surface of zero size
+        let surface = self
+            .compositor
+            .create_surface(window, size.width.max(1), size.height.max(1));
+
+        self.surface = Some(surface);
+        self.resize(&viewport);
+    }
+
+    pub(crate) fn resize(&mut self, viewport: &Viewport) {
+        let size = viewport.physical_size();
+        self.width = size.width;
+        self.height = size.height;
+
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+
+        if let Some(surface) = &mut self.surface {
+            self.compositor
+                .configure_surface(surface, size.width, size.height);
+        }
+    }
+
+    pub(crate) fn present(
+        &mut self,
+        renderer: &mut Renderer,
+        viewport: &Viewport,
+        background_color: Color,
+    ) -> Result<(), SurfaceError> {
+        let Some(surface) = &mut self.surface else {
+            return Ok(());
+        };
+
+        if self.width == 0 || self.height == 0 {
+            return Ok(());
+        }
+
+        let Renderer::Secondary(renderer) = renderer else {
+            unreachable!("software renderer is created by Software::create_renderer");
+        };
+
+        compositor::present::<String>(renderer, surface, viewport, background_color, &[])
+    }
+
+    pub(crate) fn screenshot(
+        &self,
+        renderer: &mut Renderer,
+        viewport: &Viewport,
+        background_color: Color,
+    ) -> Option<Screenshot> {
+        let Renderer::Secondary(renderer) = renderer else {
+            unreachable!("software renderer is created by Software::create_renderer");
+        };
+
+        draw_offscreen(renderer, viewport, background_color)
+    }
+}
+
+/// Draws the last frame of the renderer into a new buffer,
+/// `None` when the viewport is empty.
+pub(crate) fn draw_offscreen(
+    renderer: &mut iced_tiny_skia::Renderer,
+    viewport: &Viewport,
+    background_color: Color,
+) -> Option<Screenshot> {
+    let size = viewport.physical_size();
+    if size.width == 0 || size.height == 0 {
+        log::warn!("Can't take a screenshot of an empty viewport");
+        return None;
+    }
+
+    let mut pixels = vec![0; size.width as usize * size.height as usize * 4];
+    let mut clip_mask = tiny_skia::Mask::new(size.width, size.height).expect("Create clip mask");
+
+    renderer.draw::<String>(
+        &mut tiny_skia::PixmapMut::from_bytes(&mut pixels, size.width, size.height)
+            .expect("Create pixel map"),
+        &mut clip_mask,
+        viewport,
+        &[Rectangle::with_size(Size::new(
+            size.width as f32,
+            size.height as f32,
+        ))],
+        background_color,
+        &[],
+    );
+
+    screenshot::bgra_to_rgba(&mut pixels);
+    Some(Screenshot::new(size.width, size.height, pixels))
+}
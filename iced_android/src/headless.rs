@@ -0,0 +1,496 @@
+//! Harness which drives a [`Program`] with synthetic window events,
+//! without a window or a GPU.
+//!
+//! Events go through the same path as in the window runner, the ui
+//! is laid out with the software renderer from `iced_tiny_skia`.
+//! Tasks returned from `update` are run to completion before the next
+//! event, so a task which never finishes blocks the harness. Window
+//! actions are ignored, subscriptions aren't run but
+//! [`Headless::subscriptions`] tells how many the runner would run.
+//!
+//! ```no_run
+//! use iced_android::controls::Controls;
+//! use iced_android::headless::Headless;
+//!
+//! let mut headless = Headless::new(Controls::default(), 1080, 2400, 2.625);
+//! headless.tap(540.0, 1200.0);
+//! headless.type_text("hello");
+//! ```
+
+use futures::StreamExt;
+use iced_wgpu::graphics::Viewport;
+use iced_widget::Renderer;
+use iced_winit::core::time::{Duration, Instant};
+use iced_winit::core::{clipboard, mouse, window, Event, Point, Rectangle, Size, Theme, Vector};
+use iced_winit::futures::subscription;
+use iced_winit::runtime::program::State;
+use iced_winit::runtime::{task, Debug};
+use iced_winit::winit;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{DeviceId, ElementState, Ime, MouseButton, Touch, TouchPhase, WindowEvent};
+use winit::keyboard::{Key, KeyCode, ModifiersState, NativeKeyCode, PhysicalKey};
+
+use crate::action;
+use crate::dispatch::{Dispatch, Host};
+use crate::graphics::software;
+use crate::ime::{self, Preedit};
+use crate::input::Input;
+use crate::kinetic::Kinetic;
+use crate::{theme, AppConfig, Appearance, Program, Screenshot};
+
+// like a 60Hz display
+const FRAME: Duration = Duration::from_millis(16);
+
+/// A [`Program`] running without a window.
+pub struct Headless<P: Program> {
+    ui: Ui<P>,
+    dispatch: Dispatch,
+    // only moved by `advance`, so gestures don't depend on how fast the test runs
+    now: Instant,
+    config: AppConfig,
+}
+
+// what the window runner keeps for its window
+struct Ui<P: Program> {
+    state: State<P>,
+    kinetic: Kinetic,
+    viewport: Viewport,
+    renderer: Renderer,
+    clipboard: Clipboard,
+    debug: Debug,
+    theme: Theme,
+    appearance: Appearance,
+    // the light or dark mode of the pretend system
+    system_appearance: Appearance,
+    ime_cursor_area: Option<Rectangle>,
+    preedit: Option<Preedit>,
+    // like the runner, no subscriptions in background
+    suspended: bool,
+    subscriptions: usize,
+}
+
+impl<P: Program> Headless<P> {
+    /// Creates the harness with the physical size and scale factor of a window.
+    pub fn new(program: P, width: u32, height: u32, scale_factor: f64) -> Self {
+        Self::with_config(program, AppConfig::default(), width, height, scale_factor)
+    }
+
+    /// Uses the fonts, text size and theme from the config,
+    /// renderer and event loop settings are ignored. Like in the runner,
+    /// the assets directory of the first config is kept for the process.
+    pub fn with_config(
+        program: P,
+        config: AppConfig,
+        width: u32,
+        height: u32,
+        scale_factor: f64,
+    ) -> Self {
+        let viewport = Viewport::with_physical_size(Size::new(width, height), scale_factor);
+
+        crate::platform::set_assets_dir(&config.assets_dir);
+        crate::fonts::load(&config);
+
+        let mut debug = Debug::new();
+        let mut renderer = Renderer::Secondary(iced_tiny_skia::Renderer::new(
+            config.default_font,
+            config.default_text_size,
+        ));
+
+        let state = State::new(program, viewport.logical_size(), &mut renderer, &mut debug);
+        // light mode, like a window without a system preference
+        let appearance = Appearance::default();
+
+        let mut ui = Ui {
+            theme: theme::resolve(state.program(), &config, appearance),
+            state,
+            kinetic: Kinetic::new(config.kinetic),
+            viewport,
+            renderer,
+            clipboard: Clipboard::default(),
+            debug,
+            appearance,
+            system_appearance: appearance,
+            ime_cursor_area: None,
+            preedit: None,
+            suspended: false,
+            subscriptions: 0,
+        };
+        let dispatch = Dispatch::new(config.gestures.unwrap_or_default());
+
+        ui.appearance_changed();
+        ui.update(&dispatch.input, &config);
+
+        Self {
+            ui,
+            dispatch,
+            now: Instant::now(),
+            config,
+        }
+    }
+
+    /// Feeds a window event, the same way the window runner does.
+    pub fn window_event(&mut self, event: WindowEvent) {
+        // there is no redraw, so apply the new size right away
+        if let WindowEvent::Resized(size) = event {
+            self.ui.viewport = Viewport::with_physical_size(
+                Size::new(size.width, size.height),
+                self.ui.viewport.scale_factor(),
+            );
+        }
+
+        self.dispatch
+            .window_event(&mut self.ui, &self.config, event, self.now);
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.window_event(WindowEvent::Resized(PhysicalSize::new(width, height)));
+    }
+
+    /// Sends a touch event, position is in physical pixels.
+    pub fn touch(&mut self, id: u64, phase: TouchPhase, x: f64, y: f64) {
+        self.window_event(WindowEvent::Touch(Touch {
+            device_id: DeviceId::dummy(),
+            phase,
+            location: PhysicalPosition::new(x, y),
+            force: None,
+            id,
+        }));
+    }
+
+    /// Touches and lifts a finger at the same position.
+    pub fn tap(&mut self, x: f64, y: f64) {
+        self.touch(0, TouchPhase::Started, x, y);
+        self.touch(0, TouchPhase::Ended, x, y);
+    }
+
+    /// Moves the mouse cursor and clicks the left button.
+    pub fn click(&mut self, x: f64, y: f64) {
+        self.window_event(WindowEvent::CursorMoved {
+            device_id: DeviceId::dummy(),
+            position: PhysicalPosition::new(x, y),
+        });
+
+        for state in [ElementState::Pressed, ElementState::Released] {
+            self.window_event(WindowEvent::MouseInput {
+                device_id: DeviceId::dummy(),
+                state,
+                button: MouseButton::Left,
+            });
+        }
+    }
+
+    pub fn key_press(&mut self, code: KeyCode, key: Key) {
+        self.key(PhysicalKey::Code(code), key, ElementState::Pressed);
+    }
+
+    pub fn key_release(&mut self, code: KeyCode, key: Key) {
+        self.key(PhysicalKey::Code(code), key, ElementState::Released);
+    }
+
+    /// Presses and releases a key for every character of the text.
+    pub fn type_text(&mut self, text: &str) {
+        let physical_key = PhysicalKey::Unidentified(NativeKeyCode::Unidentified);
+
+        for c in text.chars() {
+            let key = Key::Character(c.to_string().into());
+            self.key(physical_key, key.clone(), ElementState::Pressed);
+            self.key(physical_key, key, ElementState::Released);
+        }
+    }
+
+    /// Sends an event of the input method, e.g. `Ime::Preedit` with the
+    /// composing text, drawn over the focused widget, or `Ime::Commit`
+    /// with the text typed into it.
+    pub fn ime(&mut self, ime: Ime) {
+        self.window_event(WindowEvent::Ime(ime));
+    }
+
+    /// Where the candidate window of the input method would be placed,
+    /// in logical pixels.
+    pub fn ime_cursor_area(&self) -> Option<Rectangle> {
+        self.ui.ime_cursor_area
+    }
+
+    /// The composing text drawn over the focused widget.
+    pub fn preedit(&self) -> Option<&str> {
+        self.ui
+            .preedit
+            .as_ref()
+            .map(|preedit| preedit.text.as_str())
+    }
+
+    /// Moves the clock forward, a finger which is still down can become
+    /// a long press. A fling is scrolled in frames of 16ms meanwhile.
+    pub fn advance(&mut self, duration: Duration) {
+        let end = self.now + duration;
+
+        while self.ui.kinetic.is_animating() && self.now < end {
+            self.now = (self.now + FRAME).min(end);
+
+            if self.ui.kinetic.step(
+                &mut self.ui.state,
+                &mut self.ui.renderer,
+                self.ui.viewport.logical_size(),
+                &mut self.ui.debug,
+                self.now,
+            ) {
+                self.ui
+                    .state
+                    .queue_event(Event::Window(window::Event::RedrawRequested(self.now)));
+                self.ui.update(&self.dispatch.input, &self.config);
+            }
+        }
+        self.now = end;
+
+        self.dispatch.poll(&mut self.ui, &self.config, self.now);
+    }
+
+    /// Whether a fling is still scrolling, or an overscroll glow fading.
+    pub fn is_scrolling(&self) -> bool {
+        self.ui.kinetic.is_animating()
+    }
+
+    /// Sends the app to background, like `suspended` in the runner:
+    /// fingers are lifted, the fling stops and subscriptions are stopped.
+    pub fn suspend(&mut self) {
+        self.dispatch.lose_fingers(&mut self.ui, &self.config);
+        self.ui.kinetic.reset();
+
+        self.ui.suspended = true;
+        self.ui.subscriptions = 0;
+    }
+
+    /// Brings the app back, the subscriptions are started again.
+    pub fn resume(&mut self) {
+        self.dispatch.reset();
+
+        self.ui.suspended = false;
+        self.ui.update(&self.dispatch.input, &self.config);
+    }
+
+    /// Number of subscriptions the runner would be running.
+    pub fn subscriptions(&self) -> usize {
+        self.ui.subscriptions
+    }
+
+    /// Switches the system light or dark mode, the window is told
+    /// with `ThemeChanged` like on desktop.
+    pub fn set_appearance(&mut self, appearance: Appearance) {
+        self.ui.system_appearance = appearance;
+
+        let theme = match appearance {
+            Appearance::Light => winit::window::Theme::Light,
+            Appearance::Dark => winit::window::Theme::Dark,
+        };
+        self.window_event(WindowEvent::ThemeChanged(theme));
+    }
+
+    /// The theme the ui is drawn with.
+    pub fn theme(&self) -> Theme {
+        theme::resolve(self.ui.state.program(), &self.config, self.ui.appearance)
+    }
+
+    /// Sends a message directly to the program.
+    pub fn message(&mut self, message: P::Message) {
+        self.ui.state.queue_message(message);
+        self.ui.update(&self.dispatch.input, &self.config);
+    }
+
+    pub fn program(&self) -> &P {
+        self.ui.state.program()
+    }
+
+    pub fn cursor(&self) -> mouse::Cursor {
+        self.dispatch.input.cursor(self.ui.viewport.scale_factor())
+    }
+
+    /// Number of fingers on the screen.
+    pub fn fingers(&self) -> usize {
+        self.dispatch.input.fingers()
+    }
+
+    pub fn modifiers(&self) -> ModifiersState {
+        self.dispatch.input.modifiers()
+    }
+
+    /// Toggled by the caps lock key, it's not one of the modifiers.
+    pub fn caps_lock(&self) -> bool {
+        self.dispatch.input.caps_lock()
+    }
+
+    /// Whether the Fn key is held, it's not one of the modifiers.
+    pub fn fn_key(&self) -> bool {
+        self.dispatch.input.fn_key()
+    }
+
+    pub fn mouse_interaction(&self) -> mouse::Interaction {
+        self.ui.state.mouse_interaction()
+    }
+
+    /// Renders the current ui with the software renderer,
+    /// `None` when the window is resized to nothing.
+    pub fn screenshot(&mut self) -> Option<Screenshot> {
+        let Renderer::Secondary(renderer) = &mut self.ui.renderer else {
+            unreachable!("headless always uses the software renderer");
+        };
+
+        let background_color = self.ui.state.program().background_color();
+        software::draw_offscreen(renderer, &self.ui.viewport, background_color)
+    }
+
+    pub fn clipboard(&self) -> Option<&str> {
+        self.ui.clipboard.contents.as_deref()
+    }
+
+    fn key(&mut self, physical_key: PhysicalKey, key: Key, state: ElementState) {
+        self.dispatch.key(
+            &mut self.ui,
+            &self.config,
+            physical_key,
+            key,
+            state,
+            self.now,
+        );
+    }
+}
+
+impl<P: Program> Ui<P> {
+    fn appearance_changed(&mut self) {
+        if let Some(message) = self.state.program().appearance_changed(self.appearance) {
+            self.state.queue_message(message);
+        }
+    }
+}
+
+impl<P: Program> Host<P> for Ui<P> {
+    fn state(&mut self) -> &mut State<P> {
+        &mut self.state
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.viewport.scale_factor()
+    }
+
+    // runs at least once, the theme can change without a message,
+    // messages produced by tasks are handled in the next iteration
+    fn update(&mut self, input: &Input, config: &AppConfig) {
+        loop {
+            self.theme = theme::resolve(self.state.program(), config, self.appearance);
+
+            let (_, task) = crate::update(
+                &mut self.state,
+                &self.viewport,
+                input.cursor(self.viewport.scale_factor()),
+                &mut self.renderer,
+                &self.theme,
+                &mut self.clipboard,
+                &mut self.debug,
+            );
+
+            if let Some(stream) = task.and_then(task::into_stream) {
+                for action in futures::executor::block_on(stream.collect::<Vec<_>>()) {
+                    if let Some(action) = action::perform(
+                        action,
+                        &mut self.state,
+                        &mut self.renderer,
+                        &self.viewport,
+                        &mut self.clipboard,
+                        &mut self.debug,
+                    ) {
+                        log::info!("Ignoring {action:?} without a window");
+                    }
+                }
+            }
+
+            if self.state.is_queue_empty() {
+                break;
+            }
+        }
+
+        // over the ui, every time it's drawn
+        self.kinetic.draw(&mut self.renderer, &self.theme);
+        if let Some(preedit) = &self.preedit {
+            preedit.draw(&mut self.renderer, &self.theme);
+        }
+
+        if !self.suspended {
+            let subscription = self.state.program().subscription();
+            self.subscriptions = subscription::into_recipes(subscription).len();
+        }
+    }
+
+    // there are no event listeners without subscriptions
+    fn update_synthetic(&mut self, input: &Input, config: &AppConfig) {
+        self.update(input, config);
+    }
+
+    fn fling(&mut self, position: Point, velocity: Vector, now: Instant) {
+        self.kinetic.fling(
+            &mut self.state,
+            &mut self.renderer,
+            self.viewport.logical_size(),
+            &mut self.debug,
+            position,
+            velocity,
+            now,
+        );
+    }
+
+    fn stop_fling(&mut self) {
+        self.kinetic.stop();
+    }
+
+    fn update_appearance(&mut self) -> bool {
+        if self.system_appearance == self.appearance {
+            return false;
+        }
+
+        self.appearance = self.system_appearance;
+        self.appearance_changed();
+        true
+    }
+
+    fn is_text_focused(&mut self) -> bool {
+        ime::is_text_focused(
+            &mut self.state,
+            &mut self.renderer,
+            self.viewport.logical_size(),
+            &mut self.debug,
+        )
+    }
+
+    fn find_caret(&mut self, input: &Input, config: &AppConfig, near: Point) -> Option<Rectangle> {
+        ime::find_caret(
+            &mut self.state,
+            &self.viewport,
+            input.cursor(self.viewport.scale_factor()),
+            &self.theme,
+            config,
+            &mut self.debug,
+            near,
+        )
+    }
+
+    fn set_ime_cursor_area(&mut self, position: Point, size: Size) {
+        self.ime_cursor_area = Some(Rectangle::new(position, size));
+    }
+
+    fn set_preedit(&mut self, preedit: Option<Preedit>) {
+        self.preedit = preedit;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Clipboard {
+    contents: Option<String>,
+}
+
+impl iced_core::Clipboard for Clipboard {
+    fn read(&self, _kind: clipboard::Kind) -> Option<String> {
+        self.contents.clone()
+    }
+
+    fn write(&mut self, _kind: clipboard::Kind, contents: String) {
+        self.contents = Some(contents);
+    }
+}
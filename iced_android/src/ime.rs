@@ -0,0 +1,411 @@
+//! Composition of input methods, e.g. swipe typing of Gboard or CJK input.
+//!
+//! The text widgets of iced 0.13 know nothing about input methods. The composing
+//! text is drawn underlined over the focused `text_input` or `text_editor`, at
+//! its caret, and only the committed text is typed into it with key events.
+//!
+//! The events are made up, so the runner doesn't pass them to the event
+//! listeners of the program, and tells it with `Program::composing` when
+//! a composition starts and ends, e.g. to keep it out of an undo history.
+//! When the caret is moved by a press or a key, the composing text is typed
+//! where it was, and what the input method commits next goes to the new caret.
+//!
+//! The widgets don't tell where their caret is, it's read from the quads of
+//! the ui drawn with tiny-skia, see [`find_caret`].
+//!
+//! winit reports `WindowEvent::Ime` on desktop only. On android GameActivity
+//! keeps the text of its input connection in a buffer, which is read after
+//! every iteration of the event loop with `Ime::buffer`.
+
+#[cfg(all(target_os = "android", feature = "game-activity"))]
+use std::ops::Range;
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::Arc;
+
+use iced_wgpu::graphics::text::Paragraph;
+use iced_wgpu::graphics::Viewport;
+use iced_widget::Renderer;
+use iced_winit::core::keyboard::{self, key, Key, Location, Modifiers};
+use iced_winit::core::renderer::{self, Renderer as _};
+use iced_winit::core::text::{self, LineHeight, Paragraph as _, Renderer as _};
+use iced_winit::core::widget::operation::{self, Operation};
+use iced_winit::core::widget::Id;
+use iced_winit::core::{
+    alignment, clipboard, mouse, Event, Font, Pixels, Point, Rectangle, Size, SmolStr, Theme,
+};
+use iced_winit::runtime::program::State;
+use iced_winit::runtime::Debug;
+use iced_winit::winit;
+
+use crate::{AppConfig, Program};
+
+// thickness of the line under the composing text
+const UNDERLINE: f32 = 1.0;
+
+#[derive(Debug, Default)]
+pub(crate) struct Ime {
+    // drawn over the focused widget, not typed into it
+    preedit: String,
+    // from the first preedit to the commit, winit clears the preedit before it
+    active: bool,
+    // what GameActivity's buffer was left with, only the composing text
+    #[cfg(all(target_os = "android", feature = "game-activity"))]
+    buffer: String,
+    // the buffer is emptied after a composition was finished by the app
+    #[cfg(all(target_os = "android", feature = "game-activity"))]
+    reset: bool,
+    // winit keeps sending the preedits of the composition which was
+    // finished by the app, they are ignored until it's committed
+    #[cfg(not(all(target_os = "android", feature = "game-activity")))]
+    interrupted: bool,
+    // logical, as drawn by the focused widget, `None` when there is none
+    caret: Option<Rectangle>,
+    // the last press, the caret is looked for close to it
+    pressed: Point,
+}
+
+/// The composing text, drawn over the ui with an underline.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Preedit {
+    pub(crate) text: String,
+    bounds: Rectangle,
+    font: Font,
+    size: Pixels,
+}
+
+impl Ime {
+    /// Events typing the committed text into the focused widget.
+    pub(crate) fn event(&mut self, ime: &winit::event::Ime) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        match ime {
+            winit::event::Ime::Preedit(text, _cursor) => {
+                #[cfg(not(all(target_os = "android", feature = "game-activity")))]
+                if self.interrupted {
+                    return events;
+                }
+
+                self.preedit.clone_from(text);
+                if !text.is_empty() {
+                    self.active = true;
+                }
+            }
+            winit::event::Ime::Commit(text) => {
+                #[cfg(not(all(target_os = "android", feature = "game-activity")))]
+                {
+                    self.interrupted = false;
+                }
+
+                self.type_text(&mut events, text);
+                self.preedit.clear();
+                self.active = false;
+            }
+            winit::event::Ime::Enabled => {
+                self.preedit.clear();
+                self.active = false;
+            }
+            winit::event::Ime::Disabled => {
+                // keeps the composing text
+                let preedit = std::mem::take(&mut self.preedit);
+                self.type_text(&mut events, &preedit);
+                self.active = false;
+            }
+        }
+
+        events
+    }
+
+    /// From the first preedit to the commit, even while the preedit is empty.
+    pub(crate) fn is_composing(&self) -> bool {
+        self.active
+    }
+
+    /// The caret is about to move, or another widget to be focused, while
+    /// composing. Returns the events typing the composing text where it was.
+    pub(crate) fn interrupt(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        if !self.active {
+            return events;
+        }
+
+        let preedit = std::mem::take(&mut self.preedit);
+        self.type_text(&mut events, &preedit);
+        self.active = false;
+
+        #[cfg(all(target_os = "android", feature = "game-activity"))]
+        {
+            self.buffer.clear();
+            self.reset = true;
+        }
+        #[cfg(not(all(target_os = "android", feature = "game-activity")))]
+        {
+            self.interrupted = true;
+        }
+
+        events
+    }
+
+    /// Turns the buffer of an input connection into ime events, `composing`
+    /// is the byte range of the composing text, the rest was committed.
+    ///
+    /// Returns `None` when nothing changed. Otherwise the buffer has to be
+    /// reset to [`composing_text`](Ime::composing_text), so it doesn't grow
+    /// with the whole text typed into the app.
+    #[cfg(all(target_os = "android", feature = "game-activity"))]
+    pub(crate) fn buffer(
+        &mut self,
+        text: &str,
+        composing: Option<Range<usize>>,
+    ) -> Option<Vec<winit::event::Ime>> {
+        let composing = composing
+            .filter(|range| text.get(range.clone()).is_some())
+            .unwrap_or(text.len()..text.len());
+
+        if composing == (0..text.len()) && text == self.buffer {
+            return None;
+        }
+
+        let committed = [&text[..composing.start], &text[composing.end..]].concat();
+        let preedit = text[composing].to_string();
+
+        let mut events = Vec::new();
+        if !committed.is_empty() {
+            events.push(winit::event::Ime::Commit(committed));
+        }
+        if preedit != self.buffer || !events.is_empty() {
+            events.push(winit::event::Ime::Preedit(preedit.clone(), None));
+        }
+
+        self.buffer = preedit;
+        Some(events)
+    }
+
+    #[cfg(all(target_os = "android", feature = "game-activity"))]
+    pub(crate) fn composing_text(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Whether the buffer has to be emptied, after an interrupted composition.
+    #[cfg(all(target_os = "android", feature = "game-activity"))]
+    pub(crate) fn take_reset(&mut self) -> bool {
+        std::mem::take(&mut self.reset)
+    }
+
+    /// A touch or a click is about to be handled, the caret it leaves
+    /// is looked for close to it.
+    pub(crate) fn pressed(&mut self, position: Point) {
+        self.caret = None;
+        self.pressed = position;
+    }
+
+    /// Where the caret is looked for, the last one found or the last press.
+    pub(crate) fn near(&self) -> Point {
+        self.caret.map_or(self.pressed, |caret| caret.center())
+    }
+
+    /// The caret found in the ui, `focused` tells whether a text widget
+    /// has the focus. While the caret blinks off the last one is kept.
+    pub(crate) fn located(&mut self, focused: bool, caret: Option<Rectangle>) {
+        self.caret = if focused { caret.or(self.caret) } else { None };
+    }
+
+    /// Logical area of the composing text, or of the caret, for the candidate
+    /// window of the input method.
+    pub(crate) fn cursor_area(&self, config: &AppConfig) -> Option<(Point, Size)> {
+        let caret = self.caret?;
+        let width = measure(&self.preedit, config).width.max(caret.width);
+
+        Some((caret.position(), Size::new(width, caret.height)))
+    }
+
+    /// What is drawn over the focused widget, `None` without composing text.
+    pub(crate) fn preedit(&self, config: &AppConfig) -> Option<Preedit> {
+        if self.preedit.is_empty() {
+            return None;
+        }
+        let caret = self.caret?;
+        let width = measure(&self.preedit, config).width;
+
+        Some(Preedit {
+            text: self.preedit.clone(),
+            bounds: Rectangle::new(caret.position(), Size::new(width, caret.height)),
+            font: config.default_font,
+            size: config.default_text_size,
+        })
+    }
+
+    fn type_text(&mut self, events: &mut Vec<Event>, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' => press(events, Key::Named(key::Named::Enter), None),
+                c => {
+                    let text = SmolStr::new(c.encode_utf8(&mut [0; 4]));
+                    press(events, Key::Character(text.clone()), Some(text));
+                }
+            }
+        }
+    }
+}
+
+impl Preedit {
+    /// Draws the composing text over the ui, after it's drawn. The text of the
+    /// widget after the caret is covered meanwhile.
+    pub(crate) fn draw(&self, renderer: &mut Renderer, theme: &Theme) {
+        let palette = theme.palette();
+        let b = self.bounds;
+
+        renderer.with_layer(b, |renderer| {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: b,
+                    ..renderer::Quad::default()
+                },
+                palette.background,
+            );
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle::new(
+                        Point::new(b.x, b.y + b.height - UNDERLINE),
+                        Size::new(b.width, UNDERLINE),
+                    ),
+                    ..renderer::Quad::default()
+                },
+                palette.text,
+            );
+            // in the middle of the line, like the text of the widget
+            renderer.fill_text(
+                text::Text {
+                    vertical_alignment: alignment::Vertical::Center,
+                    ..text_in(self.text.clone(), b.size(), self.font, self.size)
+                },
+                Point::new(b.x, b.center_y()),
+                palette.text,
+                b,
+            );
+        });
+    }
+}
+
+/// Whether a text widget has the focus, they are the only focusable widgets.
+pub(crate) fn is_text_focused<P: Program>(
+    state: &mut State<P>,
+    renderer: &mut Renderer,
+    size: Size,
+    debug: &mut Debug,
+) -> bool {
+    let focused = Arc::new(AtomicBool::new(false));
+
+    let operation: Box<dyn Operation> = Box::new(Focused(focused.clone()));
+    state.operate(renderer, std::iter::once(operation), size, debug);
+
+    focused.load(atomic::Ordering::Relaxed)
+}
+
+/// Where the focused text widget draws its caret, in logical pixels.
+///
+/// The operations of iced 0.13 get neither the bounds nor the cursor of
+/// a text widget. The ui is drawn again with tiny-skia, which keeps the quads
+/// until they are rendered, and the caret is the one 1px wide closest to `near`.
+/// `None` while it blinks off, or text is selected.
+pub(crate) fn find_caret<P: Program>(
+    state: &mut State<P>,
+    viewport: &Viewport,
+    cursor: mouse::Cursor,
+    theme: &Theme,
+    config: &AppConfig,
+    debug: &mut Debug,
+    near: Point,
+) -> Option<Rectangle> {
+    let mut renderer = Renderer::Secondary(iced_tiny_skia::Renderer::new(
+        config.default_font,
+        config.default_text_size,
+    ));
+    let _ = crate::update(
+        state,
+        viewport,
+        cursor,
+        &mut renderer,
+        theme,
+        &mut clipboard::Null,
+        debug,
+    );
+
+    let Renderer::Secondary(renderer) = &mut renderer else {
+        unreachable!("created as tiny-skia");
+    };
+    let distance = |bounds: &Rectangle| bounds.center().distance(near);
+
+    renderer
+        .layers()
+        .iter()
+        .flat_map(|layer| &layer.quads)
+        .map(|(quad, _)| quad.bounds)
+        // clipped to the text bounds by `text_editor`
+        .filter(|bounds| bounds.width <= 1.0 && bounds.height > 1.0)
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
+
+// operations are boxed and `Send`
+struct Focused(Arc<AtomicBool>);
+
+impl Operation for Focused {
+    fn container(
+        &mut self,
+        _id: Option<&Id>,
+        _bounds: Rectangle,
+        operate_on_children: &mut dyn FnMut(&mut dyn Operation),
+    ) {
+        operate_on_children(self);
+    }
+
+    fn focusable(&mut self, state: &mut dyn operation::Focusable, _id: Option<&Id>) {
+        if state.is_focused() {
+            self.0.store(true, atomic::Ordering::Relaxed);
+        }
+    }
+}
+
+// in the default font, like the text of the widgets
+fn measure(content: &str, config: &AppConfig) -> Size {
+    if content.is_empty() {
+        return Size::ZERO;
+    }
+
+    let text = text_in(
+        content,
+        Size::INFINITY,
+        config.default_font,
+        config.default_text_size,
+    );
+    Paragraph::with_text(text).min_bounds()
+}
+
+fn text_in<T>(content: T, bounds: Size, font: Font, size: Pixels) -> text::Text<T> {
+    text::Text {
+        content,
+        bounds,
+        size,
+        line_height: LineHeight::default(),
+        font,
+        horizontal_alignment: alignment::Horizontal::Left,
+        vertical_alignment: alignment::Vertical::Top,
+        shaping: text::Shaping::Advanced,
+        wrapping: text::Wrapping::None,
+    }
+}
+
+fn press(events: &mut Vec<Event>, key: Key, text: Option<SmolStr>) {
+    events.push(Event::Keyboard(keyboard::Event::KeyPressed {
+        key: key.clone(),
+        modified_key: key.clone(),
+        physical_key: key::Physical::Unidentified(key::NativeCode::Unidentified),
+        location: Location::Standard,
+        modifiers: Modifiers::empty(),
+        text,
+    }));
+    events.push(Event::Keyboard(keyboard::Event::KeyReleased {
+        key,
+        location: Location::Standard,
+        modifiers: Modifiers::empty(),
+    }));
+}
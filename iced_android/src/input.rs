@@ -0,0 +1,200 @@
+use iced_winit::core::{mouse, touch, Event};
+use iced_winit::{conversion, winit};
+use winit::dpi::PhysicalPosition;
+use winit::event::{ElementState, TouchPhase, WindowEvent};
+use winit::keyboard::{Key, KeyCode, ModifiersState, NamedKey, PhysicalKey};
+
+/// Cursor, fingers and modifiers state, tracked from window events.
+///
+/// Shared by the window runner and the headless harness,
+/// so both of them see exactly the same input.
+///
+/// Widgets get the touch events of every finger, the cursor follows only
+/// the primary one: the first finger which touched the screen when no other
+/// was down. It stays primary until it's lifted, and no finger takes over
+/// until all of them are lifted, like `isPrimary` of web pointer events.
+#[derive(Debug, Default)]
+pub(crate) struct Input {
+    // of the mouse
+    cursor_position: Option<PhysicalPosition<f64>>,
+    // by touch id, in the order they touched the screen
+    fingers: Vec<(u64, PhysicalPosition<f64>)>,
+    primary: Option<u64>,
+    // the cursor stays where the primary finger was lifted for the update
+    // of the lift, so buttons see it over them, see `take_lifted`
+    lifted: Option<PhysicalPosition<f64>>,
+    modifiers: ModifiersState,
+    // the modifier keys held, the left and the right one separately,
+    // so releasing one Shift while the other is down keeps Shift
+    modifier_keys: Vec<(PhysicalKey, ModifiersState)>,
+    // changed by a key event, the widgets are told with `ModifiersChanged`
+    modifiers_changed: bool,
+    // not modifiers of winit and iced, caps lock is toggled by its key
+    caps_lock: bool,
+    fn_key: bool,
+}
+
+impl Input {
+    pub(crate) fn window_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_position = Some(*position);
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor_position = None;
+            }
+            WindowEvent::Touch(touch) => {
+                self.touch(touch.id, touch.phase, touch.location);
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                self.key(
+                    event.physical_key,
+                    &event.logical_key,
+                    event.state,
+                    event.repeat,
+                );
+            }
+            _ => (),
+        }
+    }
+
+    fn touch(&mut self, id: u64, phase: TouchPhase, location: PhysicalPosition<f64>) {
+        match phase {
+            TouchPhase::Started => {
+                if self.fingers.is_empty() {
+                    self.primary = Some(id);
+                }
+                self.fingers.retain(|(finger, _)| *finger != id);
+                self.fingers.push((id, location));
+            }
+            TouchPhase::Moved => {
+                if let Some((_, position)) =
+                    self.fingers.iter_mut().find(|(finger, _)| *finger == id)
+                {
+                    *position = location;
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.fingers.retain(|(finger, _)| *finger != id);
+
+                if self.primary == Some(id) {
+                    self.primary = None;
+                    self.lifted = Some(location);
+                }
+            }
+        }
+    }
+
+    /// Whether the primary finger was lifted since the last call.
+    ///
+    /// The runner updates the ui once more after the lift, with the cursor
+    /// unavailable, so nothing stays hovered after a tap.
+    pub(crate) fn take_lifted(&mut self) -> bool {
+        self.lifted.take().is_some()
+    }
+
+    /// Lifts every finger, e.g. when the app goes to background in the middle
+    /// of a gesture, the events tell the widgets about it.
+    pub(crate) fn lose_fingers(&mut self, scale_factor: f64) -> Vec<Event> {
+        self.primary = None;
+        self.lifted = None;
+
+        self.fingers
+            .drain(..)
+            .map(|(id, position)| {
+                let position = conversion::cursor_position(position, scale_factor);
+                Event::Touch(touch::Event::FingerLost {
+                    id: touch::Finger(id),
+                    position,
+                })
+            })
+            .collect()
+    }
+
+    /// Number of fingers on the screen.
+    pub(crate) fn fingers(&self) -> usize {
+        self.fingers.len()
+    }
+
+    // ModifiersChanged is not sent on android, so modifiers have to be tracked
+    // from the key events. Caps lock and Fn have no physical key code there.
+    pub(crate) fn key(
+        &mut self,
+        physical_key: PhysicalKey,
+        logical_key: &Key,
+        state: ElementState,
+        repeat: bool,
+    ) {
+        let pressed = state == ElementState::Pressed;
+
+        let modifier = match (physical_key, logical_key) {
+            (PhysicalKey::Code(KeyCode::ShiftLeft | KeyCode::ShiftRight), _)
+            | (_, Key::Named(NamedKey::Shift)) => ModifiersState::SHIFT,
+            (PhysicalKey::Code(KeyCode::ControlLeft | KeyCode::ControlRight), _)
+            | (_, Key::Named(NamedKey::Control)) => ModifiersState::CONTROL,
+            (PhysicalKey::Code(KeyCode::AltLeft | KeyCode::AltRight), _)
+            | (_, Key::Named(NamedKey::Alt)) => ModifiersState::ALT,
+            (PhysicalKey::Code(KeyCode::SuperLeft | KeyCode::SuperRight), _)
+            | (_, Key::Named(NamedKey::Super | NamedKey::Meta)) => ModifiersState::SUPER,
+            (PhysicalKey::Code(KeyCode::CapsLock), _) | (_, Key::Named(NamedKey::CapsLock)) => {
+                if pressed && !repeat {
+                    self.caps_lock = !self.caps_lock;
+                }
+                return;
+            }
+            (PhysicalKey::Code(KeyCode::Fn), _) | (_, Key::Named(NamedKey::Fn)) => {
+                self.fn_key = pressed;
+                return;
+            }
+            _ => return,
+        };
+
+        self.modifier_keys.retain(|(key, _)| *key != physical_key);
+        if pressed {
+            self.modifier_keys.push((physical_key, modifier));
+        }
+
+        let held = self.modifier_keys.iter().any(|(_, key)| *key == modifier);
+
+        if self.modifiers.contains(modifier) != held {
+            self.modifiers.set(modifier, held);
+            self.modifiers_changed = true;
+        }
+    }
+
+    /// Whether a key event changed the modifiers since the last call.
+    pub(crate) fn take_modifiers_changed(&mut self) -> bool {
+        std::mem::take(&mut self.modifiers_changed)
+    }
+
+    pub(crate) fn caps_lock(&self) -> bool {
+        self.caps_lock
+    }
+
+    pub(crate) fn fn_key(&self) -> bool {
+        self.fn_key
+    }
+
+    pub(crate) fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
+    pub(crate) fn cursor(&self, scale_factor: f64) -> mouse::Cursor {
+        let primary = self.primary.and_then(|primary| {
+            self.fingers
+                .iter()
+                .find(|(finger, _)| *finger == primary)
+                .map(|(_, position)| *position)
+        });
+
+        self.lifted
+            .or(primary)
+            .or(self.cursor_position)
+            .map(|p| conversion::cursor_position(p, scale_factor))
+            .map(mouse::Cursor::Available)
+            .unwrap_or(mouse::Cursor::Unavailable)
+    }
+}
@@ -0,0 +1,161 @@
+use jni::objects::{JObject, JValue};
+use jni::{AttachGuard, JavaVM};
+
+//
+// some jni syntax hints
+//
+// L - class, for example: Ljava/lang/String;
+// primitives: Z - boolean, I - integer, V - void
+// for example, Rust signature fn get_text(flag: bool) -> String
+// will become "(Z)Ljava/lang/String;"
+//
+// in find_class . is replaced by $
+// docs: android/view/WindowManager.LayoutParams
+// jni:  android/view/WindowManager$LayoutParams
+// it also has some quirks:
+// https://developer.android.com/training/articles/perf-jni.html#faq_FindClass
+//
+
+pub fn call_instance_method(name: &str) {
+    log::debug!("Calling instance method from Rust: {}", name);
+    let ctx = ndk_context::android_context();
+    let vm = get_vm(&ctx);
+    let mut env = get_env(&vm);
+    let activity = unsafe { JObject::from_raw(ctx.context() as _) };
+    if let Err(e) = env.call_method(activity, name, "()V", &[]) {
+        log::error!("Error calling instance method {}: {}", name, e);
+    }
+}
+
+/// `getResources().getConfiguration().uiMode` of the activity.
+pub fn ui_mode() -> jni::errors::Result<i32> {
+    let ctx = ndk_context::android_context();
+    let vm = get_vm(&ctx);
+    let mut env = get_env(&vm);
+    let activity = unsafe { JObject::from_raw(ctx.context() as _) };
+    // the thread stays attached, so free the local references right away
+    env.with_local_frame(4, |env| {
+        let resources = env
+            .call_method(
+                activity,
+                "getResources",
+                "()Landroid/content/res/Resources;",
+                &[],
+            )?
+            .l()?;
+        let configuration = env
+            .call_method(
+                resources,
+                "getConfiguration",
+                "()Landroid/content/res/Configuration;",
+                &[],
+            )?
+            .l()?;
+        env.get_field(configuration, "uiMode", "I")?.i()
+    })
+}
+
+/// `android.os.Build.VERSION.SDK_INT`
+pub fn sdk_int() -> jni::errors::Result<i32> {
+    let ctx = ndk_context::android_context();
+    let vm = get_vm(&ctx);
+    let mut env = get_env(&vm);
+    env.with_local_frame(2, |env| {
+        env.get_static_field("android/os/Build$VERSION", "SDK_INT", "I")?
+            .i()
+    })
+}
+
+/// Colors from `android.R.color` by name, as ARGB, resolved with `Context.getColor`.
+pub fn system_colors(names: &[&str]) -> jni::errors::Result<Vec<i32>> {
+    let ctx = ndk_context::android_context();
+    let vm = get_vm(&ctx);
+    let mut env = get_env(&vm);
+    let activity = unsafe { JObject::from_raw(ctx.context() as _) };
+    env.with_local_frame(4, |env| {
+        let class = env.find_class("android/R$color")?;
+        names
+            .iter()
+            .map(|name| {
+                let id = env.get_static_field(&class, name, "I")?.i()?;
+                env.call_method(&activity, "getColor", "(I)I", &[JValue::Int(id)])?
+                    .i()
+            })
+            .collect()
+    })
+}
+
+/// `Context.getCacheDir()` of the activity, as an absolute path.
+pub fn cache_dir() -> jni::errors::Result<String> {
+    let ctx = ndk_context::android_context();
+    let vm = get_vm(&ctx);
+    let mut env = get_env(&vm);
+    let activity = unsafe { JObject::from_raw(ctx.context() as _) };
+    env.with_local_frame(4, |env| {
+        let dir = env
+            .call_method(activity, "getCacheDir", "()Ljava/io/File;", &[])?
+            .l()?;
+        let path = env
+            .call_method(dir, "getAbsolutePath", "()Ljava/lang/String;", &[])?
+            .l()?;
+        Ok(env.get_string(&path.into())?.into())
+    })
+}
+
+/// Touch thresholds of `android.view.ViewConfiguration`, distances in pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct ViewConfiguration {
+    pub touch_slop: i32,
+    pub double_tap_slop: i32,
+    pub double_tap_timeout: i32,
+    pub long_press_timeout: i32,
+    pub minimum_fling_velocity: i32,
+    pub maximum_fling_velocity: i32,
+}
+
+/// `ViewConfiguration.get(activity)`, scaled for the density of the display.
+pub fn view_configuration() -> jni::errors::Result<ViewConfiguration> {
+    let ctx = ndk_context::android_context();
+    let vm = get_vm(&ctx);
+    let mut env = get_env(&vm);
+    let activity = unsafe { JObject::from_raw(ctx.context() as _) };
+    env.with_local_frame(4, |env| {
+        let class = env.find_class("android/view/ViewConfiguration")?;
+        let configuration = env
+            .call_static_method(
+                &class,
+                "get",
+                "(Landroid/content/Context;)Landroid/view/ViewConfiguration;",
+                &[JValue::Object(&activity)],
+            )?
+            .l()?;
+        let mut int = |name| env.call_method(&configuration, name, "()I", &[])?.i();
+
+        Ok(ViewConfiguration {
+            touch_slop: int("getScaledTouchSlop")?,
+            double_tap_slop: int("getScaledDoubleTapSlop")?,
+            minimum_fling_velocity: int("getScaledMinimumFlingVelocity")?,
+            maximum_fling_velocity: int("getScaledMaximumFlingVelocity")?,
+            double_tap_timeout: env
+                .call_static_method(&class, "getDoubleTapTimeout", "()I", &[])?
+                .i()?,
+            long_press_timeout: env
+                .call_static_method(&class, "getLongPressTimeout", "()I", &[])?
+                .i()?,
+        })
+    })
+}
+
+pub fn get_vm(ctx: &ndk_context::AndroidContext) -> JavaVM {
+    unsafe { JavaVM::from_raw(ctx.vm() as _) }.unwrap_or_else(|e| {
+        log::error!("Error getting ctx.vm(): {:?}", e);
+        panic!("No JavaVM found");
+    })
+}
+
+pub fn get_env(vm: &JavaVM) -> AttachGuard {
+    vm.attach_current_thread().unwrap_or_else(|e| {
+        log::error!("Error attaching vm: {:?}", e);
+        panic!("Failed to call attach_current_thread for JavaVM");
+    })
+}
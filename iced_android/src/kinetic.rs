@@ -0,0 +1,432 @@
+//! Momentum of flings over a `scrollable`, like in the native lists.
+//!
+//! A [`Gesture::Fling`](crate::Gesture::Fling) keeps scrolling the innermost
+//! scrollable under the finger which can scroll in that direction. It slows down
+//! with the friction every frame, until it stops, reaches an edge or a finger
+//! touches the screen. The speed left at an edge is shown as a glow over it.
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use iced_widget::Renderer;
+use iced_winit::core::widget::operation::scrollable::AbsoluteOffset;
+use iced_winit::core::widget::operation::{self, Operation};
+use iced_winit::core::widget::Id;
+use iced_winit::core::Renderer as _;
+use iced_winit::core::{border, renderer, Color, Point, Rectangle, Size, Theme, Vector};
+use iced_winit::runtime::program::State;
+use iced_winit::runtime::Debug;
+
+use crate::Program;
+
+/// Speeds are in logical pixels per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    /// How fast a fling slows down, the velocity is multiplied by
+    /// `e^(-friction * seconds)`. The default of 2 is about the normal
+    /// deceleration rate of iOS, 0.998 per millisecond.
+    pub friction: f32,
+    /// Slower flings stop.
+    pub min_velocity: f32,
+    pub overscroll_glow: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            friction: 2.0,
+            min_velocity: 20.0,
+            overscroll_glow: true,
+        }
+    }
+}
+
+// thickness of the strongest glow, in logical pixels
+const GLOW_SIZE: f32 = 40.0;
+// speed at an edge which shows the strongest glow
+const GLOW_VELOCITY: f32 = 4000.0;
+// the intensity is multiplied by `e^(-GLOW_FADE * seconds)`
+const GLOW_FADE: f32 = 6.0;
+
+pub(crate) struct Kinetic {
+    // `None` when disabled
+    config: Option<Config>,
+    fling: Option<Fling>,
+    glows: Vec<Glow>,
+    // while something is animating
+    last_step: Option<Instant>,
+}
+
+struct Fling {
+    target: Target,
+    velocity: Vector,
+    // an edge is noticed when the scrollable moves less than it was asked to
+    translation: Vector,
+    delta: Vector,
+}
+
+// scrollables usually have no id, so the target is found again by its place
+// in the widget tree, and checked with its bounds
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Target {
+    index: usize,
+    bounds: Rectangle,
+}
+
+struct Glow {
+    bounds: Rectangle,
+    edge: Edge,
+    intensity: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Kinetic {
+    pub(crate) fn new(config: Option<Config>) -> Self {
+        Self {
+            config,
+            fling: None,
+            glows: Vec::new(),
+            last_step: None,
+        }
+    }
+
+    pub(crate) fn is_animating(&self) -> bool {
+        self.fling.is_some() || !self.glows.is_empty()
+    }
+
+    /// A finger touched the screen, the glows fade out on their own.
+    pub(crate) fn stop(&mut self) {
+        self.fling = None;
+    }
+
+    /// Forgets everything, e.g. when the app goes to background.
+    pub(crate) fn reset(&mut self) {
+        self.fling = None;
+        self.glows.clear();
+        self.last_step = None;
+    }
+
+    /// Starts scrolling from the velocity of a [`Gesture::Fling`](crate::Gesture::Fling),
+    /// if there is a scrollable under the finger.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn fling<P: Program>(
+        &mut self,
+        state: &mut State<P>,
+        renderer: &mut Renderer,
+        size: Size,
+        debug: &mut Debug,
+        position: Point,
+        velocity: Vector,
+        now: Instant,
+    ) {
+        if self.config.is_none() {
+            return;
+        }
+
+        let found = operate(
+            state,
+            renderer,
+            size,
+            debug,
+            Mode::Find { position, velocity },
+        );
+
+        self.fling = found.map(|found| {
+            log::debug!("Fling {velocity:?} over scrollable {:?}", found.target);
+
+            Fling {
+                target: found.target,
+                // only along the axes the scrollable can move
+                velocity: Vector::new(
+                    if found.range.x > 0.0 { velocity.x } else { 0.0 },
+                    if found.range.y > 0.0 { velocity.y } else { 0.0 },
+                ),
+                translation: found.translation,
+                delta: Vector::ZERO,
+            }
+        });
+        self.last_step = Some(now);
+    }
+
+    /// Scrolls by the distance travelled since the previous step and fades
+    /// the glows, returns whether the ui has to be drawn again.
+    pub(crate) fn step<P: Program>(
+        &mut self,
+        state: &mut State<P>,
+        renderer: &mut Renderer,
+        size: Size,
+        debug: &mut Debug,
+        now: Instant,
+    ) -> bool {
+        let Some(config) = self.config else {
+            return false;
+        };
+        if !self.is_animating() {
+            self.last_step = None;
+            return false;
+        }
+        let Some(last_step) = self.last_step.replace(now) else {
+            return false;
+        };
+        let seconds = now.saturating_duration_since(last_step).as_secs_f32();
+
+        for glow in &mut self.glows {
+            glow.intensity *= (-GLOW_FADE * seconds).exp();
+        }
+        self.glows.retain(|glow| glow.intensity > 0.01);
+
+        if let Some(fling) = self.fling.take() {
+            self.fling = self.scroll(fling, config, state, renderer, size, debug, seconds);
+        }
+
+        true
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn scroll<P: Program>(
+        &mut self,
+        mut fling: Fling,
+        config: Config,
+        state: &mut State<P>,
+        renderer: &mut Renderer,
+        size: Size,
+        debug: &mut Debug,
+        seconds: f32,
+    ) -> Option<Fling> {
+        let friction = config.friction.max(f32::EPSILON);
+        let decay = (-friction * seconds).exp();
+        // the velocity integrated over the step, the content follows
+        // the finger so the offset goes the other way
+        let delta = -fling.velocity * ((1.0 - decay) / friction);
+
+        let Some(found) = operate(
+            state,
+            renderer,
+            size,
+            debug,
+            Mode::Scroll {
+                target: fling.target,
+                delta,
+            },
+        ) else {
+            log::debug!("Scrollable of the fling is gone");
+            return None;
+        };
+
+        let moved = found.translation - fling.translation;
+        let blocked = |moved: f32, delta: f32| delta.abs() > 0.5 && moved.abs() < delta.abs() / 2.0;
+
+        if blocked(moved.x, fling.delta.x) {
+            let edge = if fling.velocity.x < 0.0 {
+                Edge::Right
+            } else {
+                Edge::Left
+            };
+            self.overscroll(config, found.bounds, edge, fling.velocity.x);
+            fling.velocity.x = 0.0;
+        }
+        if blocked(moved.y, fling.delta.y) {
+            let edge = if fling.velocity.y < 0.0 {
+                Edge::Bottom
+            } else {
+                Edge::Top
+            };
+            self.overscroll(config, found.bounds, edge, fling.velocity.y);
+            fling.velocity.y = 0.0;
+        }
+
+        fling.velocity = fling.velocity * decay;
+        fling.translation = found.translation;
+        fling.delta = delta;
+
+        let speed = fling.velocity.x.hypot(fling.velocity.y);
+        (speed >= config.min_velocity).then_some(fling)
+    }
+
+    fn overscroll(&mut self, config: Config, bounds: Rectangle, edge: Edge, velocity: f32) {
+        log::debug!("Fling reached the {edge:?} edge at {velocity}");
+
+        if config.overscroll_glow {
+            self.glows.push(Glow {
+                bounds,
+                edge,
+                intensity: (velocity.abs() / GLOW_VELOCITY).clamp(0.2, 1.0),
+            });
+        }
+    }
+
+    /// Draws the glows over the ui, after it's drawn.
+    pub(crate) fn draw(&self, renderer: &mut Renderer, theme: &Theme) {
+        let color = theme.palette().primary;
+
+        for glow in &self.glows {
+            let size = GLOW_SIZE * glow.intensity;
+            let b = glow.bounds;
+
+            // a pill centered on the edge, the outer half is clipped
+            let (position, pill) = match glow.edge {
+                Edge::Top => (Point::new(b.x, b.y - size), Size::new(b.width, 2.0 * size)),
+                Edge::Bottom => (
+                    Point::new(b.x, b.y + b.height - size),
+                    Size::new(b.width, 2.0 * size),
+                ),
+                Edge::Left => (Point::new(b.x - size, b.y), Size::new(2.0 * size, b.height)),
+                Edge::Right => (
+                    Point::new(b.x + b.width - size, b.y),
+                    Size::new(2.0 * size, b.height),
+                ),
+            };
+
+            renderer.with_layer(b, |renderer| {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: Rectangle::new(position, pill),
+                        border: border::rounded(size),
+                        ..renderer::Quad::default()
+                    },
+                    Color {
+                        a: 0.3 * glow.intensity,
+                        ..color
+                    },
+                );
+            });
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Mode {
+    // the innermost scrollable under the position, which can move
+    // along the main axis of the velocity
+    Find { position: Point, velocity: Vector },
+    Scroll { target: Target, delta: Vector },
+}
+
+struct Found {
+    target: Target,
+    // on the screen
+    bounds: Rectangle,
+    // how far the content can move
+    range: Vector,
+    translation: Vector,
+}
+
+fn operate<P: Program>(
+    state: &mut State<P>,
+    renderer: &mut Renderer,
+    size: Size,
+    debug: &mut Debug,
+    mode: Mode,
+) -> Option<Found> {
+    let found = Arc::new(Mutex::new(None));
+
+    let operation: Box<dyn Operation> = Box::new(Scrollables {
+        mode,
+        index: 0,
+        translation: Vector::ZERO,
+        content: None,
+        found: found.clone(),
+    });
+    state.operate(renderer, std::iter::once(operation), size, debug);
+
+    let found = found.lock().expect("Lock found scrollable").take();
+    found
+}
+
+struct Scrollables {
+    mode: Mode,
+    index: usize,
+    // of the scrollables around the current widget, the layout bounds
+    // moved back by it are on the screen
+    translation: Vector,
+    // a scrollable operates on its content right after itself,
+    // as a container with the same bounds
+    content: Option<(Rectangle, Vector)>,
+    // operations are boxed and `Send`
+    found: Arc<Mutex<Option<Found>>>,
+}
+
+impl Operation for Scrollables {
+    fn container(
+        &mut self,
+        _id: Option<&Id>,
+        bounds: Rectangle,
+        operate_on_children: &mut dyn FnMut(&mut dyn Operation),
+    ) {
+        let outer = self.translation;
+
+        if let Some((_, translation)) = self
+            .content
+            .take()
+            .filter(|(content, _)| *content == bounds)
+        {
+            self.translation = self.translation + translation;
+        }
+
+        operate_on_children(self);
+        self.translation = outer;
+    }
+
+    fn scrollable(
+        &mut self,
+        state: &mut dyn operation::Scrollable,
+        _id: Option<&Id>,
+        bounds: Rectangle,
+        content_bounds: Rectangle,
+        translation: Vector,
+    ) {
+        let target = Target {
+            index: self.index,
+            bounds,
+        };
+        self.index += 1;
+        self.content = Some((bounds, translation));
+
+        let found = Found {
+            target,
+            bounds: bounds - self.translation,
+            range: Vector::new(
+                (content_bounds.width - bounds.width).max(0.0),
+                (content_bounds.height - bounds.height).max(0.0),
+            ),
+            translation,
+        };
+
+        match self.mode {
+            Mode::Find { position, velocity } => {
+                let range = if velocity.x.abs() > velocity.y.abs() {
+                    found.range.x
+                } else {
+                    found.range.y
+                };
+
+                // nested scrollables come after the outer ones
+                if range > 0.0 && found.bounds.contains(position) {
+                    *self.found.lock().expect("Lock found scrollable") = Some(found);
+                }
+            }
+            Mode::Scroll {
+                target: scrolled,
+                delta,
+            } if scrolled == target => {
+                state.scroll_by(
+                    AbsoluteOffset {
+                        x: delta.x,
+                        y: delta.y,
+                    },
+                    bounds,
+                    content_bounds,
+                );
+                *self.found.lock().expect("Lock found scrollable") = Some(found);
+            }
+            Mode::Scroll { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,990 @@
+use std::any::Any;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use iced_futures::subscription;
+use iced_wgpu::graphics::compositor::SurfaceError;
+use iced_wgpu::graphics::Viewport;
+use iced_widget::Renderer;
+use iced_winit::core::{
+    event, mouse, renderer, window, Event, Point, Rectangle, Size, Theme, Vector,
+};
+use iced_winit::runtime::{self, system, Action, Debug, Task};
+use iced_winit::{conversion, winit};
+use winit::application::ApplicationHandler;
+use winit::dpi::{LogicalPosition, LogicalSize};
+use winit::event::{DeviceEvent, DeviceId, StartCause, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::window::{Window, WindowId};
+
+mod action;
+#[cfg(target_os = "android")]
+mod android;
+pub mod assets;
+#[cfg(target_os = "android")]
+mod clipboard;
+mod config;
+pub mod controls;
+#[cfg(not(target_os = "android"))]
+mod desktop;
+mod dispatch;
+mod fonts;
+pub mod gesture;
+mod graphics;
+pub mod headless;
+mod ime;
+mod input;
+#[cfg(target_os = "android")]
+pub mod java;
+pub mod kinetic;
+mod program;
+pub mod scene;
+mod screenshot;
+pub mod shader;
+pub mod shortcut;
+pub mod theme;
+
+#[cfg(target_os = "android")]
+pub use android::{run, run_with_config, Clipboard};
+pub use config::{AppConfig, Rendering};
+#[cfg(not(target_os = "android"))]
+pub use desktop::{run, run_with_config, Clipboard};
+pub use gesture::Gesture;
+pub use iced_futures::backend::default::time;
+pub use program::Program;
+pub use scene::CustomScene;
+pub use screenshot::{Diff, Screenshot};
+pub use shortcut::Shortcut;
+pub use theme::Appearance;
+pub use winit::event_loop::EventLoopProxy;
+#[cfg(target_os = "android")]
+pub use winit::platform::android::activity::AndroidApp;
+
+#[cfg(target_os = "android")]
+use android as platform;
+#[cfg(not(target_os = "android"))]
+use desktop as platform;
+use dispatch::{Dispatch, Host};
+use graphics::Graphics;
+use input::Input;
+
+#[cfg(all(feature = "native-activity", feature = "game-activity"))]
+compile_error!("features `native-activity` and `game-activity` are mutually exclusive");
+
+// winit ime support, see `ime` for the GameActivity workaround
+// https://github.com/rust-windowing/winit/pull/2993
+
+// issue with android-activity crate default_motion_filter function
+// https://github.com/rust-mobile/android-activity/issues/79
+
+fn run_app<P: Program>(
+    event_loop: EventLoop<UserEvent>,
+    config: AppConfig,
+    new_program: impl FnOnce(EventLoopProxy<UserEvent>) -> P + 'static,
+) {
+    let proxy = event_loop.create_proxy();
+
+    // before any renderer is created
+    fonts::load(&config);
+
+    let mut app = App::new(proxy, config, new_program);
+    event_loop.run_app(&mut app).expect("Should run event loop");
+}
+
+#[derive(Debug)]
+pub enum UserEvent {
+    ShowKeyboard,
+    HideKeyboard,
+    /// Loads the shaders of the scene again, see [`CustomScene::reload`].
+    ReloadScene,
+    /// Renders the current frame offscreen and sends it back,
+    /// the sender is dropped if it can't be captured.
+    Screenshot {
+        include_scene: bool,
+        sender: mpsc::Sender<Screenshot>,
+    },
+    /// Output of a task or a subscription of the program, sent by the runner itself.
+    Action(Box<dyn Any + Send>),
+    /// The gpu is initialized, sent by the runner itself.
+    GraphicsReady,
+}
+
+// consecutive Lost/Outdated errors before the device is rebuilt
+const MAX_SURFACE_ERRORS: u32 = 3;
+
+pub struct App<P: Program> {
+    proxy: EventLoopProxy<UserEvent>,
+    config: AppConfig,
+    // taken when the window is created for the first time
+    new_program: Option<Box<dyn FnOnce(EventLoopProxy<UserEvent>) -> P>>,
+    // until the gpu is initialized
+    loading: Option<Loading<P>>,
+    app_data: Option<AppData<P>>,
+    resized: bool,
+    // for the time passed between frames
+    last_redraw: Option<Instant>,
+    // input, gestures and the input method, shared with the headless harness
+    dispatch: Dispatch,
+    surface_errors: u32,
+    needs_rebuild: bool,
+    redraw_at: Option<Instant>,
+}
+
+// while the gpu is initialized on another thread, for the first window or to
+// rebuild the graphics of the app data
+struct Loading<P: Program> {
+    window: Arc<Window>,
+    // `None` when the graphics are rebuilt, the app data already has it
+    program: Option<P>,
+    // `None` after the first init failed, it's retried on resume
+    pending: Option<graphics::Pending<P::Scene>>,
+    // replayed once the graphics are ready
+    events: Vec<WindowEvent>,
+}
+
+pub struct AppData<P: Program> {
+    state: runtime::program::State<P>,
+    // runs the tasks returned from update and the subscriptions
+    runtime: action::Runtime<P::Message>,
+    window: Arc<Window>,
+    window_id: window::Id,
+    // system light or dark mode
+    appearance: Appearance,
+    graphics: Graphics<P::Scene>,
+    renderer: Renderer,
+    clipboard: Clipboard,
+    viewport: Viewport,
+    debug: Debug,
+    // scrolling after a fling
+    kinetic: kinetic::Kinetic,
+    // composing text of the input method
+    preedit: Option<ime::Preedit>,
+    // in background, subscriptions are started again on resume
+    suspended: bool,
+}
+
+impl<P: Program> App<P> {
+    pub fn new(
+        proxy: EventLoopProxy<UserEvent>,
+        config: AppConfig,
+        new_program: impl FnOnce(EventLoopProxy<UserEvent>) -> P + 'static,
+    ) -> Self {
+        Self {
+            proxy,
+            config,
+            new_program: Some(Box::new(new_program)),
+            loading: None,
+            app_data: None,
+            resized: false,
+            last_redraw: None,
+            dispatch: Dispatch::default(),
+            surface_errors: 0,
+            needs_rebuild: false,
+            redraw_at: None,
+        }
+    }
+
+    fn create_app_data(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        window: Arc<Window>,
+        program: P,
+        graphics: Graphics<P::Scene>,
+    ) {
+        let physical_size = window.inner_size();
+        let viewport = Viewport::with_physical_size(
+            Size::new(physical_size.width, physical_size.height),
+            window.scale_factor(),
+        );
+        let clipboard = platform::clipboard(window.clone());
+        let appearance = platform::appearance(&window).unwrap_or_default();
+        theme::refresh_dynamic();
+
+        let gestures = self
+            .config
+            .gestures
+            .or_else(|| platform::gesture_config(window.scale_factor()))
+            .unwrap_or_default();
+        log::info!("Gesture thresholds {gestures:?}");
+        self.dispatch = Dispatch::new(gestures);
+
+        let mut debug = Debug::new();
+        let mut renderer = graphics.create_renderer(&self.config);
+
+        let state = runtime::program::State::new(
+            program,
+            viewport.logical_size(),
+            &mut renderer,
+            &mut debug,
+        );
+
+        event_loop.set_control_flow(self.config.control_flow);
+
+        let mut app_data = AppData {
+            state,
+            runtime: action::runtime(self.proxy.clone()),
+            window,
+            window_id: window::Id::unique(),
+            appearance,
+            graphics,
+            renderer,
+            clipboard,
+            viewport,
+            debug,
+            kinetic: kinetic::Kinetic::new(self.config.kinetic),
+            preedit: None,
+            suspended: false,
+        };
+        if let Some(message) = app_data.state.program().appearance_changed(appearance) {
+            app_data.state.queue_message(message);
+        }
+        // also subscribes
+        app_data.update(&self.dispatch.input, &self.config);
+        app_data.window.request_redraw();
+        self.app_data = Some(app_data);
+    }
+
+    // called from `suspended` too, which then suspends the new app data
+    fn graphics_ready(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        loading: Loading<P>,
+        graphics: Graphics<P::Scene>,
+    ) {
+        let window_id = loading.window.id();
+        match loading.program {
+            Some(program) => self.create_app_data(event_loop, loading.window, program, graphics),
+            None => self.graphics_rebuilt(graphics),
+        }
+
+        log::info!("Replaying {} window events", loading.events.len());
+        for event in loading.events {
+            self.window_event(event_loop, window_id, event);
+        }
+    }
+
+    // the first init is retried on resume, a rebuild on the next redraw
+    fn graphics_failed(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        mut loading: Loading<P>,
+        error: graphics::Error,
+    ) {
+        if loading.program.is_some() {
+            log::error!("Failed to initialize graphics: {error}, retrying on resume");
+            loading.pending = None;
+            self.loading = Some(loading);
+            return;
+        }
+
+        log::error!("Failed to rebuild graphics: {error}");
+        self.needs_rebuild = true;
+        self.surface_errors += 1;
+        self.redraw_at = Some(Instant::now() + backoff(self.surface_errors));
+
+        let window_id = loading.window.id();
+        for event in loading.events {
+            self.window_event(event_loop, window_id, event);
+        }
+    }
+
+    // drops the device and everything created with it, the program state is kept
+    fn rebuild_graphics(&mut self) {
+        let Some(app_data) = self.app_data.as_mut() else {
+            return;
+        };
+
+        // only one surface can be connected to the native window
+        app_data.graphics.suspend();
+
+        if self.config.rendering == Rendering::Software {
+            let graphics = Graphics::software(&self.config, app_data.window.clone());
+            self.graphics_rebuilt(graphics);
+            return;
+        }
+
+        // as slow as the first init
+        log::info!("Rebuilding graphics");
+        let pending = graphics::Pending::start(
+            &self.config,
+            app_data.window.clone(),
+            app_data.state.program().background_color(),
+            self.proxy.clone(),
+        );
+        self.loading = Some(Loading {
+            window: app_data.window.clone(),
+            program: None,
+            pending: Some(pending),
+            events: Vec::new(),
+        });
+    }
+
+    fn graphics_rebuilt(&mut self, graphics: Graphics<P::Scene>) {
+        let Some(app_data) = self.app_data.as_mut() else {
+            return;
+        };
+
+        log::info!("Graphics rebuilt");
+        app_data.renderer = graphics.create_renderer(&self.config);
+        app_data.graphics = graphics;
+        self.surface_errors = 0;
+        self.resized = true;
+        app_data.window.request_redraw();
+    }
+}
+
+impl<P: Program> ApplicationHandler<UserEvent> for App<P> {
+    fn new_events(&mut self, _event_loop: &ActiveEventLoop, _cause: StartCause) {
+        // log::info!("New events cause {:?}", cause);
+    }
+
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        log::info!("Resumed");
+
+        // fingers lifted while the app was in background
+        self.dispatch.reset();
+
+        if let Some(app_data) = self.app_data.as_mut() {
+            if self.loading.is_some() {
+                log::info!("Still rebuilding graphics");
+            } else if self.needs_rebuild {
+                log::info!("Graphics will be rebuilt on the next redraw");
+            } else {
+                log::info!("Already initialized, recreating the surface");
+                if let Err(error) = app_data.graphics.resume(app_data.window.clone()) {
+                    log::error!("Failed to resume: {error}, rebuilding graphics");
+                    self.needs_rebuild = true;
+                }
+            }
+            // the mode could be switched while in background
+            if app_data.update_appearance() {
+                app_data.update(&self.dispatch.input, &self.config);
+            }
+            app_data.update_viewport(&self.dispatch.input, &self.config);
+            app_data.suspended = false;
+            app_data.subscribe();
+            app_data.window.request_redraw();
+            return;
+        }
+
+        if let Some(loading) = self.loading.as_mut() {
+            match (&loading.pending, &loading.program) {
+                (None, Some(program)) => {
+                    log::info!("Initializing graphics again");
+                    loading.pending = Some(graphics::Pending::start(
+                        &self.config,
+                        loading.window.clone(),
+                        program.background_color(),
+                        self.proxy.clone(),
+                    ));
+                }
+                _ => log::info!("Still initializing graphics"),
+            }
+            return;
+        }
+
+        let attrs = platform::window_attributes();
+        let window = Arc::new(event_loop.create_window(attrs).unwrap());
+        // NativeActivity has no input connection, the soft keyboard sends key events
+        #[cfg(any(feature = "game-activity", not(target_os = "android")))]
+        window.set_ime_allowed(true);
+
+        let new_program = self.new_program.take().expect("Program is created once");
+        let program = new_program(self.proxy.clone());
+
+        if self.config.rendering == Rendering::Software {
+            let graphics = Graphics::software(&self.config, window.clone());
+            self.create_app_data(event_loop, window, program, graphics);
+            return;
+        }
+
+        // adapter and device creation can take long enough for an ANR
+        let pending = graphics::Pending::start(
+            &self.config,
+            window.clone(),
+            program.background_color(),
+            self.proxy.clone(),
+        );
+        self.loading = Some(Loading {
+            window,
+            program: Some(program),
+            pending: Some(pending),
+            events: Vec::new(),
+        });
+    }
+
+    fn suspended(&mut self, event_loop: &ActiveEventLoop) {
+        log::info!("Suspended");
+
+        if let Some(mut loading) = self.loading.take() {
+            if let Some(pending) = loading.pending.take() {
+                // the thread uses the native window, which is gone when this returns
+                log::info!("Waiting for the gpu init");
+                match pending.wait(&self.config, loading.window.clone()) {
+                    Ok(graphics) => self.graphics_ready(event_loop, loading, graphics),
+                    Err(error) => self.graphics_failed(event_loop, loading, error),
+                }
+            } else {
+                self.loading = Some(loading);
+            }
+        }
+
+        // the native window is destroyed, so is the surface,
+        // everything else is kept until the next resume
+        if let Some(app_data) = self.app_data.as_mut() {
+            // widgets pressed by a finger are released
+            self.dispatch.lose_fingers(app_data, &self.config);
+            app_data.kinetic.reset();
+
+            app_data.graphics.suspend();
+            // timers and streams don't run in background
+            app_data.suspended = true;
+            app_data.unsubscribe();
+        }
+        // don't count the time in background
+        self.last_redraw = None;
+    }
+
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: UserEvent) {
+        match event {
+            UserEvent::ShowKeyboard => {
+                platform::show_keyboard();
+            }
+            UserEvent::HideKeyboard => {
+                platform::hide_keyboard();
+            }
+            UserEvent::ReloadScene => {
+                let Some(app_data) = self.app_data.as_mut() else {
+                    return;
+                };
+
+                app_data.graphics.reload_scene();
+                app_data.window.request_redraw();
+            }
+            UserEvent::Screenshot {
+                include_scene,
+                sender,
+            } => {
+                let Some(app_data) = self.app_data.as_mut() else {
+                    log::warn!("Screenshot requested before the window is created");
+                    return;
+                };
+
+                let background_color = app_data.state.program().background_color();
+                if let Some(screenshot) = app_data.graphics.screenshot(
+                    &mut app_data.renderer,
+                    &app_data.viewport,
+                    background_color,
+                    include_scene,
+                ) {
+                    let _ = sender.send(screenshot);
+                }
+            }
+            UserEvent::Action(action) => {
+                let Some(app_data) = self.app_data.as_mut() else {
+                    return;
+                };
+
+                match action.downcast::<Action<P::Message>>() {
+                    Ok(action) => {
+                        app_data.perform(event_loop, *action, &self.dispatch.input, &self.config)
+                    }
+                    Err(_) => log::warn!("Ignoring an action of another program"),
+                }
+            }
+            UserEvent::GraphicsReady => {
+                let Some(loading) = self.loading.as_ref() else {
+                    return;
+                };
+                // the init is retried on resume
+                let Some(pending) = &loading.pending else {
+                    return;
+                };
+                let Some(result) = pending.finish(&self.config, loading.window.clone()) else {
+                    return;
+                };
+
+                let loading = self.loading.take().expect("Loading is checked above");
+                match result {
+                    Ok(graphics) => self.graphics_ready(event_loop, loading, graphics),
+                    Err(error) => self.graphics_failed(event_loop, loading, error),
+                }
+            }
+        }
+    }
+
+    fn device_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        _device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        log::trace!("DeviceEvent {:?}", event);
+    }
+
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        _window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        log::trace!("Window event: {:?}", event);
+
+        if let Some(loading) = self.loading.as_mut() {
+            match event {
+                WindowEvent::CloseRequested => event_loop.exit(),
+                // the first frame is requested when the app data is created
+                WindowEvent::RedrawRequested => {}
+                event => loading.events.push(event),
+            }
+            return;
+        }
+
+        if self.needs_rebuild && event == WindowEvent::RedrawRequested {
+            self.needs_rebuild = false;
+            // redrawn once the new graphics are ready
+            self.rebuild_graphics();
+            return;
+        }
+
+        let Some(app_data) = self.app_data.as_mut() else {
+            return;
+        };
+
+        match event {
+            WindowEvent::CloseRequested => {
+                event_loop.exit();
+            }
+            WindowEvent::RedrawRequested => {
+                if !app_data.graphics.has_surface() {
+                    log::info!("No surface while suspended, skipping redraw");
+                    return;
+                }
+
+                if self.resized {
+                    let size = app_data.window.inner_size();
+
+                    app_data.viewport = Viewport::with_physical_size(
+                        Size::new(size.width, size.height),
+                        app_data.window.scale_factor(),
+                    );
+                    app_data.graphics.resize(&app_data.viewport);
+
+                    self.resized = false;
+                }
+
+                let now = Instant::now();
+                let dt = self
+                    .last_redraw
+                    .map_or(Duration::ZERO, |last_redraw| now - last_redraw);
+                self.last_redraw = Some(now);
+
+                let mut animating = false;
+                if let Some(scene) = app_data.graphics.scene_mut() {
+                    app_data.state.program().update_scene(scene);
+                    scene.update(dt);
+                    animating = scene.is_animating();
+                }
+
+                if app_data.step_kinetic(now) {
+                    app_data.update(&self.dispatch.input, &self.config);
+                }
+                animating |= app_data.kinetic.is_animating();
+
+                // for window::frames()
+                app_data.broadcast(Event::Window(window::Event::RedrawRequested(now)));
+
+                let background_color = app_data.state.program().background_color();
+
+                match app_data.graphics.present(
+                    &mut app_data.renderer,
+                    &app_data.viewport,
+                    background_color,
+                ) {
+                    Ok(()) => {
+                        self.surface_errors = 0;
+
+                        if animating {
+                            app_data.window.request_redraw();
+                        }
+
+                        app_data.window.set_cursor(conversion::mouse_interaction(
+                            app_data.state.mouse_interaction(),
+                        ));
+                    }
+                    Err(error @ (SurfaceError::Lost | SurfaceError::Outdated))
+                        if self.surface_errors < MAX_SURFACE_ERRORS =>
+                    {
+                        log::warn!("Surface error: {error}, reconfiguring the surface");
+                        self.surface_errors += 1;
+                        self.resized = true;
+                        app_data.window.request_redraw();
+                    }
+                    Err(SurfaceError::Timeout) => {
+                        self.surface_errors += 1;
+                        let delay = backoff(self.surface_errors);
+                        log::warn!("Surface timeout, next redraw in {delay:?}");
+                        self.redraw_at = Some(Instant::now() + delay);
+                    }
+                    Err(error) => {
+                        // out of memory, or reconfiguring didn't help
+                        log::error!("Surface error: {error}, rebuilding device and surface");
+                        self.needs_rebuild = true;
+                        app_data.window.request_redraw();
+                    }
+                }
+            }
+            WindowEvent::Resized(_) => {
+                self.resized = true;
+            }
+            _ => (),
+        }
+
+        if self
+            .dispatch
+            .window_event(app_data, &self.config, event, Instant::now())
+        {
+            app_data.window.request_redraw();
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let now = Instant::now();
+
+        if let Some(app_data) = self.app_data.as_mut() {
+            // a long press has no event of its own
+            if self.dispatch.poll(app_data, &self.config, now) {
+                app_data.window.request_redraw();
+            }
+
+            // winit drops the text events of GameActivity
+            #[cfg(all(target_os = "android", feature = "game-activity"))]
+            for ime in platform::ime_events(&mut self.dispatch.ime) {
+                if self.dispatch.ime(app_data, &self.config, &ime) {
+                    app_data.window.request_redraw();
+                }
+            }
+        }
+
+        if self.redraw_at.is_some_and(|redraw_at| now >= redraw_at) {
+            self.redraw_at = None;
+
+            if let Some(app_data) = &self.app_data {
+                app_data.window.request_redraw();
+            }
+        }
+
+        match self
+            .redraw_at
+            .into_iter()
+            .chain(self.dispatch.gestures.deadline())
+            .min()
+        {
+            Some(wake_up) => event_loop.set_control_flow(ControlFlow::WaitUntil(wake_up)),
+            None => event_loop.set_control_flow(self.config.control_flow),
+        }
+    }
+
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        log::info!("Exiting");
+
+        // also saves the pipeline cache
+        if let Some(app_data) = self.app_data.as_mut() {
+            app_data.graphics.suspend();
+        }
+    }
+}
+
+// 16ms, 32ms, 64ms, ... up to 1s
+fn backoff(errors: u32) -> Duration {
+    Duration::from_millis(16 << errors.saturating_sub(1).min(6))
+}
+
+impl<P: Program> AppData<P> {
+    // the window could be resized or rotated while in background
+    fn update_viewport(&mut self, input: &Input, config: &AppConfig) {
+        let size = self.window.inner_size();
+        let viewport = Viewport::with_physical_size(
+            Size::new(size.width, size.height),
+            self.window.scale_factor(),
+        );
+
+        if viewport.physical_size() == self.viewport.physical_size()
+            && viewport.scale_factor() == self.viewport.scale_factor()
+        {
+            return;
+        }
+
+        self.viewport = viewport;
+        self.state.queue_event(Event::Window(window::Event::Resized(
+            self.viewport.logical_size(),
+        )));
+        self.update(input, config);
+    }
+
+    // queues the message of the program if the system mode was switched,
+    // returns whether the theme could change
+    fn update_appearance(&mut self) -> bool {
+        let colors_changed = theme::refresh_dynamic();
+
+        let Some(appearance) = platform::appearance(&self.window) else {
+            return colors_changed;
+        };
+
+        if appearance == self.appearance {
+            return colors_changed;
+        }
+
+        log::info!("Appearance changed to {appearance:?}");
+        self.appearance = appearance;
+        if let Some(message) = self.state.program().appearance_changed(appearance) {
+            self.state.queue_message(message);
+        }
+
+        true
+    }
+
+    fn update(&mut self, input: &Input, config: &AppConfig) {
+        self.run(input, config, true);
+    }
+
+    // `listeners` is false for the events made up for the input method
+    fn run(&mut self, input: &Input, config: &AppConfig, listeners: bool) {
+        let theme = theme::resolve(self.state.program(), config, self.appearance);
+
+        let (uncaptured_events, task) = update(
+            &mut self.state,
+            &self.viewport,
+            input.cursor(self.viewport.scale_factor()),
+            &mut self.renderer,
+            &theme,
+            &mut self.clipboard,
+            &mut self.debug,
+        );
+        self.kinetic.draw(&mut self.renderer, &theme);
+        if let Some(preedit) = &self.preedit {
+            preedit.draw(&mut self.renderer, &theme);
+        }
+
+        if listeners {
+            for event in uncaptured_events {
+                self.broadcast(event);
+            }
+        }
+
+        if let Some(stream) = task.and_then(runtime::task::into_stream) {
+            self.runtime.run(stream);
+        }
+
+        // the program decides what to listen to from its current state
+        self.subscribe();
+    }
+
+    // also called after messages which arrive in background, e.g. the
+    // output of a task, the subscriptions wait for the resume then
+    fn subscribe(&mut self) {
+        if self.suspended {
+            return;
+        }
+
+        let subscription = self
+            .runtime
+            .enter(|| self.state.program().subscription())
+            .map(Action::Output);
+
+        self.runtime.track(subscription::into_recipes(subscription));
+    }
+
+    fn unsubscribe(&mut self) {
+        self.runtime.track([]);
+    }
+
+    // scrolls the scrollable of a fling, returns whether the ui has to be updated
+    fn step_kinetic(&mut self, now: Instant) -> bool {
+        let changed = self.kinetic.step(
+            &mut self.state,
+            &mut self.renderer,
+            self.viewport.logical_size(),
+            &mut self.debug,
+            now,
+        );
+
+        // the scrollables notify `on_scroll` on redraw
+        if changed {
+            self.state
+                .queue_event(Event::Window(window::Event::RedrawRequested(now)));
+        }
+        changed
+    }
+
+    // event listeners only get the events which weren't captured by a widget
+    fn broadcast(&mut self, event: Event) {
+        self.runtime.broadcast(subscription::Event::Interaction {
+            window: self.window_id,
+            event,
+            status: event::Status::Ignored,
+        });
+    }
+
+    fn perform(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        action: Action<P::Message>,
+        input: &Input,
+        config: &AppConfig,
+    ) {
+        let action = action::perform(
+            action,
+            &mut self.state,
+            &mut self.renderer,
+            &self.viewport,
+            &mut self.clipboard,
+            &mut self.debug,
+        );
+
+        match action {
+            None => {}
+            Some(Action::Window(action)) => self.window_action(event_loop, action),
+            Some(Action::System(system::Action::QueryInformation(_))) => {
+                log::warn!("System information is not available");
+            }
+            Some(Action::Exit) => event_loop.exit(),
+            Some(action) => unreachable!("{action:?} is performed by action::perform"),
+        }
+
+        if !self.state.is_queue_empty() {
+            self.update(input, config);
+        }
+        // operations can change the ui too, e.g. focus
+        self.window.request_redraw();
+    }
+
+    // there is only one window, so ids are ignored
+    fn window_action(&mut self, event_loop: &ActiveEventLoop, action: runtime::window::Action) {
+        use runtime::window::Action;
+
+        match action {
+            Action::Close(_) => event_loop.exit(),
+            Action::GetOldest(channel) | Action::GetLatest(channel) => {
+                let _ = channel.send(Some(self.window_id));
+            }
+            Action::GetSize(_, channel) => {
+                let _ = channel.send(self.viewport.logical_size());
+            }
+            Action::GetScaleFactor(_, channel) => {
+                let _ = channel.send(self.viewport.scale_factor() as f32);
+            }
+            Action::Screenshot(_, channel) => {
+                let background_color = self.state.program().background_color();
+                if let Some(screenshot) = self.graphics.screenshot(
+                    &mut self.renderer,
+                    &self.viewport,
+                    background_color,
+                    true,
+                ) {
+                    let size = Size::new(screenshot.width(), screenshot.height());
+                    let _ = channel.send(runtime::window::Screenshot::new(
+                        screenshot.into_rgba(),
+                        size,
+                        self.viewport.scale_factor(),
+                    ));
+                }
+            }
+            _ => log::warn!("Window action is not supported, ignoring"),
+        }
+    }
+}
+
+impl<P: Program> Host<P> for AppData<P> {
+    fn state(&mut self) -> &mut runtime::program::State<P> {
+        &mut self.state
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.window.scale_factor()
+    }
+
+    fn update(&mut self, input: &Input, config: &AppConfig) {
+        AppData::update(self, input, config);
+    }
+
+    fn update_synthetic(&mut self, input: &Input, config: &AppConfig) {
+        self.run(input, config, false);
+    }
+
+    fn fling(&mut self, position: Point, velocity: Vector, now: Instant) {
+        self.kinetic.fling(
+            &mut self.state,
+            &mut self.renderer,
+            self.viewport.logical_size(),
+            &mut self.debug,
+            position,
+            velocity,
+            now,
+        );
+    }
+
+    fn stop_fling(&mut self) {
+        self.kinetic.stop();
+    }
+
+    fn update_appearance(&mut self) -> bool {
+        AppData::update_appearance(self)
+    }
+
+    fn is_text_focused(&mut self) -> bool {
+        ime::is_text_focused(
+            &mut self.state,
+            &mut self.renderer,
+            self.viewport.logical_size(),
+            &mut self.debug,
+        )
+    }
+
+    fn find_caret(&mut self, input: &Input, config: &AppConfig, near: Point) -> Option<Rectangle> {
+        let theme = theme::resolve(self.state.program(), config, self.appearance);
+
+        ime::find_caret(
+            &mut self.state,
+            &self.viewport,
+            input.cursor(self.viewport.scale_factor()),
+            &theme,
+            config,
+            &mut self.debug,
+            near,
+        )
+    }
+
+    fn set_ime_cursor_area(&mut self, position: Point, size: Size) {
+        self.window.set_ime_cursor_area(
+            LogicalPosition::new(position.x, position.y),
+            LogicalSize::new(size.width, size.height),
+        );
+    }
+
+    fn set_preedit(&mut self, preedit: Option<ime::Preedit>) {
+        self.preedit = preedit;
+    }
+}
+
+fn update<P: Program>(
+    state: &mut runtime::program::State<P>,
+    viewport: &Viewport,
+    cursor: mouse::Cursor,
+    renderer: &mut Renderer,
+    theme: &Theme,
+    clipboard: &mut dyn iced_core::Clipboard,
+    debug: &mut Debug,
+) -> (Vec<Event>, Option<Task<P::Message>>) {
+    state.update(
+        viewport.logical_size(),
+        cursor,
+        renderer,
+        theme,
+        &renderer::Style {
+            text_color: theme.palette().text,
+        },
+        clipboard,
+        debug,
+    )
+}
@@ -0,0 +1,65 @@
+use iced_futures::Subscription;
+use iced_widget::Renderer;
+use iced_winit::core::{Color, Theme};
+use iced_winit::runtime;
+
+use crate::gesture::Gesture;
+use crate::{Appearance, CustomScene, Shortcut};
+
+/// A [`runtime::Program`] which can be driven by [`run`](crate::run).
+///
+/// Messages are `'static`, as the tasks returned from `update` send them
+/// through the event loop.
+pub trait Program:
+    runtime::Program<Theme = Theme, Renderer = Renderer, Message: 'static> + 'static
+{
+    /// Drawn underneath the ui, `()` for no scene.
+    type Scene: CustomScene;
+
+    /// The color used to clear the frame before the scene is drawn.
+    fn background_color(&self) -> Color {
+        Color::BLACK
+    }
+
+    /// Called before every frame, to pass the program state to the scene.
+    fn update_scene(&self, _scene: &mut Self::Scene) {}
+
+    /// The theme of the ui, `None` to use [`AppConfig::theme`](crate::AppConfig::theme),
+    /// or to follow the system if it's not set either.
+    fn theme(&self, _appearance: Appearance) -> Option<Theme> {
+        None
+    }
+
+    /// Called with the system light or dark mode when the program is created,
+    /// and every time the user switches it. The message is sent to `update`.
+    fn appearance_changed(&self, _appearance: Appearance) -> Option<Self::Message> {
+        None
+    }
+
+    /// Called with the gestures recognized from the touch events, which are
+    /// still delivered to the widgets as well. The message is sent to `update`.
+    fn gesture(&self, _gesture: Gesture) -> Option<Self::Message> {
+        None
+    }
+
+    /// Called with the editing shortcuts which the widgets don't handle, like
+    /// undo and redo, see [`shortcut`](crate::shortcut). The message is sent to `update`.
+    fn shortcut(&self, _shortcut: Shortcut) -> Option<Self::Message> {
+        None
+    }
+
+    /// Called with `true` when an input method starts composing text in the
+    /// focused widget, and `false` when the composition is committed or finished.
+    /// The composing text is drawn over the widget, only the committed text is
+    /// typed into it, e.g. an undo history can record the whole composition once.
+    /// The message is sent to `update`.
+    fn composing(&self, _composing: bool) -> Option<Self::Message> {
+        None
+    }
+
+    /// Timers, event listeners and other streams of messages, checked again
+    /// after every update. They are stopped while the app is in background.
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+}
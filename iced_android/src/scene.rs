@@ -0,0 +1,403 @@
+//! Custom wgpu content drawn underneath the ui.
+
+use std::time::Duration;
+
+use iced_wgpu::graphics::Viewport;
+use iced_wgpu::wgpu;
+use iced_winit::core::Color;
+
+use crate::shader::{self, ShaderError};
+
+/// Rendered with wgpu before the ui, into the same render pass
+/// which clears the frame with [`Program::background_color`](crate::Program::background_color).
+///
+/// The runner calls `resize`, `update` and `prepare` in this order before `draw`.
+/// The scene is created on the thread which initializes the gpu, again if the
+/// device is rebuilt, and it's not drawn with software rendering.
+///
+/// Pipelines created with the `pipeline_cache` are saved in the cache directory
+/// of the app, so the next launch doesn't compile them again. It's `None` when
+/// the backend doesn't support it.
+pub trait CustomScene: Sized + Send + 'static {
+    fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        pipeline_cache: Option<&wgpu::PipelineCache>,
+    ) -> Self;
+
+    /// Called after creation, and when the window size or scale factor changes.
+    fn resize(&mut self, _viewport: &Viewport) {}
+
+    /// Called every frame with the time passed since the previous one.
+    fn update(&mut self, _dt: Duration) {}
+
+    /// Upload buffers and textures before the render pass begins.
+    fn prepare(&mut self, _queue: &wgpu::Queue) {}
+
+    /// While `true`, frames are drawn continuously instead of on events.
+    fn is_animating(&self) -> bool {
+        false
+    }
+
+    /// Loads shaders and other assets again, on [`UserEvent::ReloadScene`](crate::UserEvent::ReloadScene).
+    fn reload(&mut self, _device: &wgpu::Device, _pipeline_cache: Option<&wgpu::PipelineCache>) {}
+
+    /// Shown over the ui while set, e.g. a shader compile error.
+    fn error(&self) -> Option<&str> {
+        None
+    }
+
+    fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>);
+}
+
+/// No scene, only the background color.
+impl CustomScene for () {
+    fn new(
+        _device: &wgpu::Device,
+        _format: wgpu::TextureFormat,
+        _pipeline_cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+    }
+
+    fn draw<'a>(&'a self, _render_pass: &mut wgpu::RenderPass<'a>) {}
+}
+
+/// The red triangle from the iced integration example.
+pub struct Triangle {
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl CustomScene for Triangle {
+    fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        pipeline_cache: Option<&wgpu::PipelineCache>,
+    ) -> Triangle {
+        let (vs_module, fs_module) = (
+            device.create_shader_module(wgpu::include_wgsl!("vert.wgsl")),
+            device.create_shader_module(wgpu::include_wgsl!("frag.wgsl")),
+        );
+        let pipeline = build_pipeline(
+            device,
+            format,
+            (&vs_module, "main"),
+            (&fs_module, "main"),
+            &[],
+            pipeline_cache,
+        );
+
+        Triangle { pipeline }
+    }
+
+    fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Rotating triangle, driven by a uniform buffer with the elapsed time,
+/// viewport size, scale factor and a color set by the program.
+///
+/// The shader is loaded from `shaders/animated.wgsl` in the assets,
+/// the built-in copy is used if it's missing.
+pub struct Animated {
+    format: wgpu::TextureFormat,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    color: Color,
+    resolution: [f32; 2],
+    scale_factor: f32,
+    // seconds since the scene was created, not counting the pauses
+    time: f64,
+    paused: bool,
+    error: Option<String>,
+}
+
+// matches `Uniforms` in animated.wgsl, padded to 16 bytes
+const UNIFORMS_SIZE: u64 = 48;
+
+const SHADER_PATH: &str = "shaders/animated.wgsl";
+const BUILT_IN_SHADER: &str = include_str!("../assets/shaders/animated.wgsl");
+
+impl Animated {
+    /// Expected in srgb, like the colors of the ui.
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    /// Stops the animation and the continuous redraw.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    fn uniforms(&self) -> [u8; UNIFORMS_SIZE as usize] {
+        let [r, g, b, a] = self.color.into_linear();
+        let [width, height] = self.resolution;
+        // an f32 of the seconds gets choppy after a few hours, the shader
+        // gets the whole seconds and the fraction separately
+        let seconds = self.time.trunc() as f32;
+        let fraction = self.time.fract() as f32;
+        let values = [
+            r,
+            g,
+            b,
+            a,
+            width,
+            height,
+            seconds,
+            fraction,
+            self.scale_factor,
+        ];
+
+        let mut bytes = [0; UNIFORMS_SIZE as usize];
+        for (chunk, value) in bytes.chunks_exact_mut(4).zip(values) {
+            chunk.copy_from_slice(&value.to_ne_bytes());
+        }
+        bytes
+    }
+
+    fn load_pipeline(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        pipeline_cache: Option<&wgpu::PipelineCache>,
+    ) -> Result<wgpu::RenderPipeline, ShaderError> {
+        let module = shader::load(device, SHADER_PATH)?;
+
+        // e.g. entry points or bindings not matching the layout
+        shader::validation_scope(device, || {
+            build_pipeline(
+                device,
+                format,
+                (&module, "vs_main"),
+                (&module, "fs_main"),
+                &[bind_group_layout],
+                pipeline_cache,
+            )
+        })
+        .map_err(|message| ShaderError::Invalid {
+            path: SHADER_PATH.to_owned(),
+            message,
+        })
+    }
+
+    fn built_in_pipeline(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        pipeline_cache: Option<&wgpu::PipelineCache>,
+    ) -> wgpu::RenderPipeline {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(SHADER_PATH),
+            source: wgpu::ShaderSource::Wgsl(BUILT_IN_SHADER.into()),
+        });
+
+        build_pipeline(
+            device,
+            format,
+            (&module, "vs_main"),
+            (&module, "fs_main"),
+            &[bind_group_layout],
+            pipeline_cache,
+        )
+    }
+}
+
+impl CustomScene for Animated {
+    fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        pipeline_cache: Option<&wgpu::PipelineCache>,
+    ) -> Animated {
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("animated scene uniforms"),
+            size: UNIFORMS_SIZE,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: wgpu::BufferSize::new(UNIFORMS_SIZE),
+                },
+                count: None,
+            }],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniforms.as_entire_binding(),
+            }],
+        });
+
+        // the built-in shader is only compiled when the asset can't be used
+        let mut error = None;
+        let loaded = Self::load_pipeline(device, format, &bind_group_layout, pipeline_cache);
+        let pipeline = match loaded {
+            Ok(pipeline) => pipeline,
+            Err(load_error) => {
+                if load_error.is_not_found() {
+                    log::info!("{SHADER_PATH} is not in the assets, using the built-in shader");
+                } else {
+                    log::error!("Failed to load the scene shader: {load_error}");
+                    error = Some(load_error.to_string());
+                }
+
+                Self::built_in_pipeline(device, format, &bind_group_layout, pipeline_cache)
+            }
+        };
+
+        Animated {
+            format,
+            bind_group_layout,
+            pipeline,
+            uniforms,
+            bind_group,
+            color: Color::from_rgb(1.0, 0.0, 0.0),
+            resolution: [1.0, 1.0],
+            scale_factor: 1.0,
+            time: 0.0,
+            paused: false,
+            error,
+        }
+    }
+
+    fn resize(&mut self, viewport: &Viewport) {
+        let size = viewport.physical_size();
+        // the shader divides by the resolution
+        self.resolution = [size.width.max(1) as f32, size.height.max(1) as f32];
+        self.scale_factor = viewport.scale_factor() as f32;
+    }
+
+    fn update(&mut self, dt: Duration) {
+        if !self.paused {
+            self.time += dt.as_secs_f64();
+        }
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue) {
+        queue.write_buffer(&self.uniforms, 0, &self.uniforms());
+    }
+
+    fn is_animating(&self) -> bool {
+        !self.paused
+    }
+
+    // keeps the previous pipeline if the new shader is broken
+    fn reload(&mut self, device: &wgpu::Device, pipeline_cache: Option<&wgpu::PipelineCache>) {
+        match Self::load_pipeline(device, self.format, &self.bind_group_layout, pipeline_cache) {
+            Ok(pipeline) => {
+                log::info!("Reloaded {SHADER_PATH}");
+                self.pipeline = pipeline;
+                self.error = None;
+            }
+            Err(error) => {
+                log::error!("Failed to reload the scene shader: {error}");
+                self.error = Some(error.to_string());
+            }
+        }
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+pub(crate) fn clear<'a>(
+    target: &'a wgpu::TextureView,
+    encoder: &'a mut wgpu::CommandEncoder,
+    background_color: Color,
+) -> wgpu::RenderPass<'a> {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: None,
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear({
+                    let [r, g, b, a] = background_color.into_linear();
+
+                    wgpu::Color {
+                        r: r as f64,
+                        g: g as f64,
+                        b: b as f64,
+                        a: a as f64,
+                    }
+                }),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    })
+}
+
+fn build_pipeline(
+    device: &wgpu::Device,
+    texture_format: wgpu::TextureFormat,
+    (vs_module, vs_entry_point): (&wgpu::ShaderModule, &str),
+    (fs_module, fs_entry_point): (&wgpu::ShaderModule, &str),
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        push_constant_ranges: &[],
+        bind_group_layouts,
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: vs_module,
+            entry_point: vs_entry_point,
+            buffers: &[],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: fs_module,
+            entry_point: fs_entry_point,
+            targets: &[Some(wgpu::ColorTargetState {
+                format: texture_format,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent::REPLACE,
+                    alpha: wgpu::BlendComponent::REPLACE,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            front_face: wgpu::FrontFace::Ccw,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache,
+    })
+}
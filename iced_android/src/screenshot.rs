@@ -0,0 +1,172 @@
+use std::io;
+use std::path::Path;
+
+/// Pixels of a rendered frame, read back from the gpu or the software buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Screenshot {
+    /// `rgba` has 4 bytes per pixel, rows are not padded.
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Self {
+        assert_eq!(
+            rgba.len(),
+            width as usize * height as usize * 4,
+            "rgba buffer doesn't match the size"
+        );
+
+        Self {
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    pub fn into_rgba(self) -> Vec<u8> {
+        self.rgba
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.rgba[i],
+            self.rgba[i + 1],
+            self.rgba[i + 2],
+            self.rgba[i + 3],
+        ]
+    }
+
+    pub fn encode_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut bytes = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgba)?;
+        writer.finish()?;
+
+        Ok(bytes)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), png::EncodingError> {
+        let bytes = self.encode_png()?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    pub fn decode_png(bytes: impl io::Read) -> Result<Self, png::DecodingError> {
+        let mut decoder = png::Decoder::new(bytes);
+        // expand palettes and low bit depths to 8 bits per channel
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+
+        let rgba = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+            png::ColorType::Indexed => unreachable!("palette is expanded by the decoder"),
+        };
+
+        Ok(Self::new(info.width, info.height, rgba))
+    }
+
+    pub fn open_png(path: impl AsRef<Path>) -> Result<Self, png::DecodingError> {
+        let file = std::fs::File::open(path)?;
+        Self::decode_png(io::BufReader::new(file))
+    }
+
+    /// Compares with another screenshot of the same size, channels which
+    /// differ by no more than `tolerance` are considered equal.
+    ///
+    /// Returns `None` if the sizes don't match.
+    pub fn diff(&self, other: &Screenshot, tolerance: u8) -> Option<Diff> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+
+        let mut pixels = 0;
+        let mut max = 0;
+        let mut rgba = Vec::with_capacity(self.rgba.len());
+
+        for (a, b) in self.rgba.chunks_exact(4).zip(other.rgba.chunks_exact(4)) {
+            let difference = a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| a.abs_diff(*b))
+                .max()
+                .unwrap_or(0);
+            max = max.max(difference);
+
+            if difference > tolerance {
+                pixels += 1;
+                rgba.extend([255, 0, 0, 255]);
+            } else {
+                // faded original, so the differences stand out
+                rgba.extend([a[0] / 4, a[1] / 4, a[2] / 4, 255]);
+            }
+        }
+
+        Some(Diff {
+            pixels,
+            max,
+            image: Screenshot::new(self.width, self.height, rgba),
+        })
+    }
+}
+
+/// Result of [`Screenshot::diff`].
+#[derive(Debug, Clone)]
+pub struct Diff {
+    /// Number of pixels differing by more than the tolerance.
+    pub pixels: usize,
+    /// Largest difference of a single channel.
+    pub max: u8,
+    /// Differing pixels in red over the darkened first screenshot.
+    pub image: Screenshot,
+}
+
+impl Diff {
+    pub fn ratio(&self) -> f64 {
+        let total = self.image.width as f64 * self.image.height as f64;
+        if total == 0.0 {
+            0.0
+        } else {
+            self.pixels as f64 / total
+        }
+    }
+}
+
+// iced_tiny_skia draws in softbuffer's 0RGB format, which is BGRA in memory
+pub(crate) fn bgra_to_rgba(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
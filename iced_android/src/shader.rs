@@ -0,0 +1,84 @@
+//! Loading wgsl shaders from the assets at runtime.
+//!
+//! Shaders are validated with naga before they reach wgpu, and wgpu
+//! errors are caught with an error scope, so a broken shader is
+//! reported instead of panicking in `create_shader_module`.
+
+use std::fmt;
+use std::io;
+
+use iced_wgpu::wgpu;
+
+use crate::assets;
+
+#[derive(Debug)]
+pub enum ShaderError {
+    Read { path: String, error: io::Error },
+    Invalid { path: String, message: String },
+}
+
+impl ShaderError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, ShaderError::Read { error, .. } if error.kind() == io::ErrorKind::NotFound)
+    }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderError::Read { path, error } => write!(f, "{path}: {error}"),
+            ShaderError::Invalid { path, message } => write!(f, "{path}:\n{message}"),
+        }
+    }
+}
+
+/// Reads and validates a shader from the assets.
+pub fn load(device: &wgpu::Device, path: &str) -> Result<wgpu::ShaderModule, ShaderError> {
+    let source = assets::read_to_string(path).map_err(|error| ShaderError::Read {
+        path: path.to_owned(),
+        error,
+    })?;
+
+    let invalid = |message| ShaderError::Invalid {
+        path: path.to_owned(),
+        message,
+    };
+
+    validate(&source).map_err(invalid)?;
+
+    validation_scope(device, || {
+        device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(path),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        })
+    })
+    .map_err(invalid)
+}
+
+/// Parses and validates wgsl, the error is formatted with the source lines.
+pub fn validate(source: &str) -> Result<(), String> {
+    let module =
+        naga::front::wgsl::parse_str(source).map_err(|error| error.emit_to_string(source))?;
+
+    // wgpu checks the device limits and features on its own
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|error| error.emit_to_string(source))?;
+
+    Ok(())
+}
+
+/// Runs `f` and returns the wgpu validation error it caused, if any,
+/// instead of passing it to the uncaptured error handler which panics.
+pub fn validation_scope<T>(device: &wgpu::Device, f: impl FnOnce() -> T) -> Result<T, String> {
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let value = f();
+
+    match futures::executor::block_on(device.pop_error_scope()) {
+        Some(error) => Err(error.to_string()),
+        None => Ok(value),
+    }
+}
@@ -0,0 +1,198 @@
+//! Editing shortcuts of hardware keyboards, on top of the ones of the widgets.
+//!
+//! `text_input` and `text_editor` handle Ctrl+A/C/X/V, Home/End and word
+//! navigation with Ctrl+arrows themselves, once they get the modifiers.
+//! The runner adds:
+//! - Fn+arrows, and Search+arrows on Chromebooks, as Home/End/PageUp/PageDown,
+//!   Search+Backspace as Delete
+//! - [`Shortcut`]s for undo and redo, which the widgets don't have, passed to
+//!   [`Program::shortcut`](crate::Program::shortcut)
+//!
+//! [`History`] keeps the values to undo, and [`text_editor`] the key bindings
+//! deleting words with Ctrl+Backspace and Ctrl+Delete.
+
+use iced_widget::text_editor::{Binding, KeyPress, Motion, Status};
+use iced_winit::core::keyboard::{self, key, Key};
+use iced_winit::core::SmolStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shortcut {
+    /// Ctrl+Z
+    Undo,
+    /// Ctrl+Y or Ctrl+Shift+Z
+    Redo,
+}
+
+// Cmd is the command key on macos, Search on a Chromebook is Super
+// and doesn't move the cursor there
+const SEARCH_NAVIGATES: bool = !cfg!(target_os = "macos");
+
+/// Adjusts a key press before it reaches the widgets.
+pub(crate) fn key_press(event: keyboard::Event, caps_lock: bool, fn_key: bool) -> keyboard::Event {
+    let keyboard::Event::KeyPressed {
+        key,
+        modified_key,
+        physical_key,
+        modifiers,
+        location,
+        text,
+    } = event
+    else {
+        return event;
+    };
+
+    let navigation = fn_key || (SEARCH_NAVIGATES && modifiers.logo());
+    let named = match key.as_ref() {
+        Key::Named(named) if navigation => match named {
+            key::Named::ArrowLeft => Some(key::Named::Home),
+            key::Named::ArrowRight => Some(key::Named::End),
+            key::Named::ArrowUp => Some(key::Named::PageUp),
+            key::Named::ArrowDown => Some(key::Named::PageDown),
+            key::Named::Backspace => Some(key::Named::Delete),
+            _ => None,
+        },
+        _ => None,
+    };
+    let (key, modified_key) = match named {
+        Some(named) => (Key::Named(named), Key::Named(named)),
+        None => (key, modified_key),
+    };
+
+    // winit leaves it empty on android, the character is in the key
+    let text = text.or_else(|| match modified_key.as_ref() {
+        Key::Character(c) => Some(SmolStr::new(c)),
+        _ => None,
+    });
+
+    // chords don't type, Ctrl+Z is not a "z", Ctrl+Alt is AltGr on some layouts
+    let chord = (modifiers.control() && !modifiers.alt()) || modifiers.logo();
+
+    let text = text.filter(|_| !chord).map(|text| {
+        // for keyboards whose character map ignores it
+        if caps_lock && !modifiers.shift() {
+            SmolStr::new(text.to_uppercase())
+        } else {
+            text
+        }
+    });
+
+    keyboard::Event::KeyPressed {
+        key,
+        modified_key,
+        physical_key,
+        modifiers,
+        location,
+        text,
+    }
+}
+
+pub(crate) fn shortcut(event: &keyboard::Event) -> Option<Shortcut> {
+    let keyboard::Event::KeyPressed { key, modifiers, .. } = event else {
+        return None;
+    };
+
+    if !modifiers.command() {
+        return None;
+    }
+
+    match key.as_ref() {
+        Key::Character("z") if modifiers.shift() => Some(Shortcut::Redo),
+        Key::Character("z") => Some(Shortcut::Undo),
+        Key::Character("y") => Some(Shortcut::Redo),
+        _ => None,
+    }
+}
+
+/// Default key bindings of `text_editor`, deleting whole words with
+/// Ctrl+Backspace and Ctrl+Delete, like `text_input` does.
+///
+/// ```no_run
+/// # use iced_widget::{text_editor, Renderer, Theme};
+/// # #[derive(Clone)] enum Message { Edit(text_editor::Action) }
+/// # let content = text_editor::Content::<Renderer>::new();
+/// let editor: text_editor::TextEditor<_, _, Theme, Renderer> = text_editor(&content)
+///     .on_action(Message::Edit)
+///     .key_binding(iced_android::shortcut::text_editor);
+/// ```
+pub fn text_editor<Message>(key_press: KeyPress) -> Option<Binding<Message>> {
+    if key_press.status == Status::Focused && key_press.modifiers.jump() {
+        match key_press.key.as_ref() {
+            Key::Named(key::Named::Backspace) => {
+                return Some(Binding::Sequence(vec![
+                    Binding::Select(Motion::WordLeft),
+                    Binding::Backspace,
+                ]));
+            }
+            Key::Named(key::Named::Delete) => {
+                return Some(Binding::Sequence(vec![
+                    Binding::Select(Motion::WordRight),
+                    Binding::Delete,
+                ]));
+            }
+            _ => {}
+        }
+    }
+
+    Binding::from_key_press(key_press)
+}
+
+/// Previous values of an edited text, or anything else, for undo and redo.
+///
+/// [`record`](History::record) the value before every edit, and restore the one
+/// returned from [`undo`](History::undo) or [`redo`](History::redo).
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    limit: usize,
+}
+
+impl<T: PartialEq> History<T> {
+    /// Keeps at most `limit` values to undo, the oldest are dropped first.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Called with the value before an edit, which can't be redone anymore.
+    pub fn record(&mut self, previous: T) {
+        if self.undo.last() == Some(&previous) {
+            return;
+        }
+
+        self.undo.push(previous);
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// The value to restore, `current` can be redone.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// The value to restore, `current` can be undone again.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+// enough for a text field, every typed character is a value
+impl<T: PartialEq> Default for History<T> {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
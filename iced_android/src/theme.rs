@@ -0,0 +1,114 @@
+//! Light and dark mode of the system, and its Material You colors.
+
+use std::sync::Mutex;
+
+use iced_winit::core::theme::Palette;
+use iced_winit::core::Theme;
+
+use crate::{platform, AppConfig, Program};
+
+/// Whether the system is in light or dark mode.
+///
+/// Read from `Configuration.uiMode` on android, and from the window on desktop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Appearance {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl Appearance {
+    /// The built-in iced theme of this mode.
+    pub fn theme(self) -> Theme {
+        match self {
+            Appearance::Light => Theme::Light,
+            Appearance::Dark => Theme::Dark,
+        }
+    }
+}
+
+// android.R.color names, the tones are from the Material 3 color roles
+const SYSTEM_COLORS: [&str; 5] = [
+    "system_neutral1_10",
+    "system_neutral1_50",
+    "system_neutral1_900",
+    "system_accent1_200",
+    "system_accent1_600",
+];
+
+// light and dark, read by the runner at startup, on resume and on
+// configuration changes, so programs can call `dynamic` on every update
+static DYNAMIC: Mutex<Option<(Theme, Theme)>> = Mutex::new(None);
+
+/// A theme with the wallpaper-based colors of Android 12 and newer.
+///
+/// `None` on older versions, on desktop, and before the window is created.
+/// Success and danger colors are the ones of [`Appearance::theme`].
+pub fn dynamic(appearance: Appearance) -> Option<Theme> {
+    let dynamic = DYNAMIC.lock().expect("Lock dynamic themes");
+    let (light, dark) = dynamic.as_ref()?;
+
+    Some(match appearance {
+        Appearance::Light => light.clone(),
+        Appearance::Dark => dark.clone(),
+    })
+}
+
+/// The palette of [`dynamic`].
+pub fn dynamic_palette(appearance: Appearance) -> Option<Palette> {
+    dynamic(appearance).map(|theme| theme.palette())
+}
+
+// reads the system colors again, returns whether they changed
+pub(crate) fn refresh_dynamic() -> bool {
+    let themes = platform::system_colors(&SYSTEM_COLORS).map(|colors| {
+        let [neutral_10, neutral_50, neutral_900, accent_200, accent_600] = colors[..] else {
+            unreachable!("a color for every name");
+        };
+
+        let light = Palette {
+            background: neutral_10,
+            text: neutral_900,
+            primary: accent_600,
+            ..Theme::Light.palette()
+        };
+        let dark = Palette {
+            background: neutral_900,
+            text: neutral_50,
+            primary: accent_200,
+            ..Theme::Dark.palette()
+        };
+
+        (light, dark)
+    });
+
+    let mut dynamic = DYNAMIC.lock().expect("Lock dynamic themes");
+    let current = dynamic
+        .as_ref()
+        .map(|(light, dark)| (light.palette(), dark.palette()));
+
+    if current == themes {
+        return false;
+    }
+
+    *dynamic = themes.map(|(light, dark)| {
+        (
+            Theme::custom("Material You Light".to_owned(), light),
+            Theme::custom("Material You Dark".to_owned(), dark),
+        )
+    });
+
+    true
+}
+
+// the program decides first, then the config, then the system
+pub(crate) fn resolve<P: Program>(
+    program: &P,
+    config: &AppConfig,
+    appearance: Appearance,
+) -> Theme {
+    program
+        .theme(appearance)
+        .or_else(|| config.theme.clone())
+        .unwrap_or_else(|| appearance.theme())
+}
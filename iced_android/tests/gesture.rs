@@ -0,0 +1,241 @@
+//! Gestures recognized from synthetic touch sequences, with a fake clock.
+
+use std::time::{Duration, Instant};
+
+use iced_android::gesture::{Config, Recognizer};
+use iced_android::headless::Headless;
+use iced_android::Gesture;
+use iced_widget::{horizontal_space, Renderer};
+use iced_winit::core::{touch, Element, Point, Theme};
+use iced_winit::runtime::{Program, Task};
+use iced_winit::winit::event::TouchPhase;
+
+const MS: Duration = Duration::from_millis(1);
+
+fn pressed(id: u64, x: f32, y: f32) -> touch::Event {
+    touch::Event::FingerPressed {
+        id: touch::Finger(id),
+        position: Point::new(x, y),
+    }
+}
+
+fn moved(id: u64, x: f32, y: f32) -> touch::Event {
+    touch::Event::FingerMoved {
+        id: touch::Finger(id),
+        position: Point::new(x, y),
+    }
+}
+
+fn lifted(id: u64, x: f32, y: f32) -> touch::Event {
+    touch::Event::FingerLifted {
+        id: touch::Finger(id),
+        position: Point::new(x, y),
+    }
+}
+
+#[test]
+fn tap_then_double_tap() {
+    let mut recognizer = Recognizer::default();
+    let start = Instant::now();
+
+    assert_eq!(recognizer.touch(pressed(0, 10.0, 10.0), start), None);
+    assert_eq!(
+        recognizer.touch(lifted(0, 12.0, 10.0), start + 50 * MS),
+        Some(Gesture::Tap {
+            position: Point::new(12.0, 10.0)
+        })
+    );
+
+    recognizer.touch(pressed(1, 20.0, 10.0), start + 200 * MS);
+    assert_eq!(
+        recognizer.touch(lifted(1, 20.0, 10.0), start + 250 * MS),
+        Some(Gesture::DoubleTap {
+            position: Point::new(20.0, 10.0)
+        })
+    );
+
+    // a third tap starts over
+    recognizer.touch(pressed(2, 20.0, 10.0), start + 300 * MS);
+    assert!(matches!(
+        recognizer.touch(lifted(2, 20.0, 10.0), start + 350 * MS),
+        Some(Gesture::Tap { .. })
+    ));
+}
+
+#[test]
+fn slow_second_tap_is_not_a_double_tap() {
+    let mut recognizer = Recognizer::default();
+    let start = Instant::now();
+
+    recognizer.touch(pressed(0, 10.0, 10.0), start);
+    recognizer.touch(lifted(0, 10.0, 10.0), start + 50 * MS);
+
+    recognizer.touch(pressed(1, 10.0, 10.0), start + 500 * MS);
+    assert!(matches!(
+        recognizer.touch(lifted(1, 10.0, 10.0), start + 550 * MS),
+        Some(Gesture::Tap { .. })
+    ));
+}
+
+#[test]
+fn moving_beyond_touch_slop_is_not_a_tap() {
+    let mut recognizer = Recognizer::default();
+    let start = Instant::now();
+
+    recognizer.touch(pressed(0, 10.0, 10.0), start);
+    recognizer.touch(moved(0, 30.0, 10.0), start + 500 * MS);
+
+    assert_eq!(recognizer.deadline(), None);
+    assert_eq!(recognizer.poll(start + 600 * MS), None);
+    // too slow for a fling
+    assert_eq!(
+        recognizer.touch(lifted(0, 30.0, 10.0), start + 700 * MS),
+        None
+    );
+}
+
+#[test]
+fn long_press_after_timeout() {
+    let config = Config::default();
+    let mut recognizer = Recognizer::new(config);
+    let start = Instant::now();
+
+    recognizer.touch(pressed(0, 10.0, 10.0), start);
+    // jitter within the slop
+    recognizer.touch(moved(0, 12.0, 11.0), start + 100 * MS);
+
+    let deadline = start + config.long_press_timeout;
+    assert_eq!(recognizer.deadline(), Some(deadline));
+    assert_eq!(recognizer.poll(deadline - MS), None);
+    assert_eq!(
+        recognizer.poll(deadline),
+        Some(Gesture::LongPress {
+            position: Point::new(12.0, 11.0)
+        })
+    );
+    // reported once, and the lift is not a tap
+    assert_eq!(recognizer.poll(deadline + 100 * MS), None);
+    assert_eq!(
+        recognizer.touch(lifted(0, 12.0, 11.0), deadline + 200 * MS),
+        None
+    );
+}
+
+#[test]
+fn fling_velocity_is_measured_at_the_end() {
+    let mut recognizer = Recognizer::default();
+    let start = Instant::now();
+
+    recognizer.touch(pressed(0, 100.0, 500.0), start);
+    // slow at first, then 10 logical pixels per millisecond upwards
+    recognizer.touch(moved(0, 100.0, 490.0), start + 200 * MS);
+    for step in 1..=5 {
+        recognizer.touch(
+            moved(0, 100.0, 490.0 - 100.0 * step as f32),
+            start + (200 + 10 * step) * MS,
+        );
+    }
+
+    let Some(Gesture::Fling { position, velocity }) =
+        recognizer.touch(lifted(0, 100.0, -10.0), start + 250 * MS)
+    else {
+        panic!("Expected a fling");
+    };
+
+    assert_eq!(position, Point::new(100.0, -10.0));
+    // 10000 pixels per second, capped by the max fling velocity
+    assert_eq!(velocity.x, 0.0);
+    assert!((velocity.y + 8000.0).abs() < 0.1, "{velocity:?}");
+}
+
+#[test]
+fn pinch_scale_is_relative_to_previous_pinch() {
+    let mut recognizer = Recognizer::default();
+    let start = Instant::now();
+
+    recognizer.touch(pressed(0, 100.0, 100.0), start);
+    recognizer.touch(pressed(1, 200.0, 100.0), start + 10 * MS);
+
+    // within the slop, resting fingers
+    assert_eq!(
+        recognizer.touch(moved(1, 202.0, 100.0), start + 20 * MS),
+        None
+    );
+
+    assert_eq!(
+        recognizer.touch(moved(1, 300.0, 100.0), start + 30 * MS),
+        Some(Gesture::Pinch {
+            center: Point::new(200.0, 100.0),
+            scale: 2.0,
+        })
+    );
+    assert_eq!(
+        recognizer.touch(moved(0, 200.0, 100.0), start + 40 * MS),
+        Some(Gesture::Pinch {
+            center: Point::new(250.0, 100.0),
+            scale: 0.5,
+        })
+    );
+
+    // no tap or fling from the finger left on the screen
+    assert_eq!(
+        recognizer.touch(lifted(1, 300.0, 100.0), start + 50 * MS),
+        None
+    );
+    assert_eq!(
+        recognizer.touch(lifted(0, 200.0, 100.0), start + 60 * MS),
+        None
+    );
+}
+
+#[derive(Default)]
+struct Gestures {
+    recognized: Vec<Gesture>,
+}
+
+impl Program for Gestures {
+    type Theme = Theme;
+    type Message = Gesture;
+    type Renderer = Renderer;
+
+    fn update(&mut self, gesture: Gesture) -> Task<Gesture> {
+        self.recognized.push(gesture);
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Gesture, Theme, Renderer> {
+        horizontal_space().into()
+    }
+}
+
+impl iced_android::Program for Gestures {
+    type Scene = ();
+
+    fn gesture(&self, gesture: Gesture) -> Option<Gesture> {
+        Some(gesture)
+    }
+}
+
+#[test]
+fn program_gets_gestures_in_logical_pixels() {
+    let mut headless = Headless::new(Gestures::default(), 400, 800, 2.0);
+
+    headless.touch(0, TouchPhase::Started, 100.0, 100.0);
+    headless.advance(Duration::from_secs(1));
+    headless.touch(0, TouchPhase::Ended, 100.0, 100.0);
+
+    headless.advance(Duration::from_secs(1));
+    headless.tap(200.0, 200.0);
+
+    assert_eq!(
+        headless.program().recognized,
+        [
+            Gesture::LongPress {
+                position: Point::new(50.0, 50.0)
+            },
+            Gesture::Tap {
+                position: Point::new(100.0, 100.0)
+            },
+        ]
+    );
+}
@@ -0,0 +1,135 @@
+//! Compares the built-in examples with the reference images in `tests/golden`.
+//!
+//! Run with `UPDATE_GOLDEN=1` to write new references after an intended change,
+//! on a mismatch the actual image and the diff are written to the target dir.
+//!
+//! Text is drawn with the font in `tests/fonts`, so the images don't depend
+//! on the fonts installed on the machine.
+
+use std::path::PathBuf;
+
+use iced_android::controls::{Controls, Example, Message};
+use iced_android::headless::Headless;
+use iced_android::{AppConfig, Appearance, Screenshot};
+use iced_winit::core::Font;
+
+// antialiasing and font hinting differ slightly between machines
+const TOLERANCE: u8 = 16;
+const MAX_DIFF_RATIO: f64 = 0.002;
+
+struct Device {
+    name: &'static str,
+    width: u32,
+    height: u32,
+    scale_factor: f64,
+    appearance: Appearance,
+}
+
+// Pixel 7
+const PHONE: Device = Device {
+    name: "phone",
+    width: 1080,
+    height: 2400,
+    scale_factor: 2.625,
+    appearance: Appearance::Light,
+};
+
+// Galaxy Watch 4, Wear OS is always dark
+const WATCH: Device = Device {
+    name: "watch",
+    width: 450,
+    height: 450,
+    scale_factor: 2.0,
+    appearance: Appearance::Dark,
+};
+
+// Inter, under the OFL in `tests/fonts/Inter-LICENSE`
+fn config() -> AppConfig {
+    AppConfig::default()
+        .assets_dir(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests"))
+        .font("fonts/Inter-Regular.ttf")
+        .default_font(Font::with_name("Inter"))
+}
+
+fn render(example: Example, device: &Device) -> Screenshot {
+    let mut headless = Headless::with_config(
+        Controls::default(),
+        config(),
+        device.width,
+        device.height,
+        device.scale_factor,
+    );
+    headless.set_appearance(device.appearance);
+    headless.message(Message::ExampleSelected(example));
+    headless.screenshot().unwrap()
+}
+
+fn check(example: Example) {
+    for device in [PHONE, WATCH] {
+        let name = format!("{}_{}", example.to_string().to_lowercase(), device.name);
+        assert_golden(&name, &render(example, &device));
+    }
+}
+
+fn assert_golden(name: &str, actual: &Screenshot) {
+    let reference_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.png"));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(reference_path.parent().unwrap()).unwrap();
+        actual.save_png(&reference_path).unwrap();
+        return;
+    }
+
+    let reference = Screenshot::open_png(&reference_path).unwrap_or_else(|error| {
+        panic!(
+            "Can't open {}: {error}, run with UPDATE_GOLDEN=1 to create it",
+            reference_path.display()
+        )
+    });
+
+    let out_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let actual_path = out_dir.join(format!("{name}.actual.png"));
+
+    let Some(diff) = actual.diff(&reference, TOLERANCE) else {
+        actual.save_png(&actual_path).unwrap();
+        panic!(
+            "{name}: size is {}x{}, reference is {}x{}, actual image is in {}",
+            actual.width(),
+            actual.height(),
+            reference.width(),
+            reference.height(),
+            actual_path.display()
+        );
+    };
+
+    if diff.ratio() > MAX_DIFF_RATIO {
+        let diff_path = out_dir.join(format!("{name}.diff.png"));
+        actual.save_png(&actual_path).unwrap();
+        diff.image.save_png(&diff_path).unwrap();
+        panic!(
+            "{name}: {} pixels differ, max channel difference {}, see {} and {}",
+            diff.pixels,
+            diff.max,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+#[test]
+fn integration_example() {
+    check(Example::Integration);
+}
+
+#[test]
+fn counter_example() {
+    check(Example::Counter);
+}
+
+#[test]
+fn text_editor_example() {
+    check(Example::TextEditor);
+}
@@ -0,0 +1,190 @@
+use iced_android::controls::{Controls, Example, Message};
+use iced_android::headless::Headless;
+use iced_android::{AppConfig, Appearance, Program};
+use iced_winit::core::{mouse, Color, Point, Theme};
+use iced_winit::futures::subscription::{self, Recipe};
+use iced_winit::winit::event::TouchPhase;
+use iced_winit::winit::keyboard::{Key, KeyCode, NamedKey};
+
+// Pixel 7 screen
+const WIDTH: u32 = 1080;
+const HEIGHT: u32 = 2400;
+const SCALE_FACTOR: f64 = 2.625;
+
+fn headless() -> Headless<Controls> {
+    Headless::new(Controls::default(), WIDTH, HEIGHT, SCALE_FACTOR)
+}
+
+#[test]
+fn modifiers_are_tracked_from_key_events() {
+    let mut headless = headless();
+
+    headless.key_press(KeyCode::ShiftLeft, Key::Named(NamedKey::Shift));
+    assert!(headless.modifiers().shift_key());
+
+    headless.key_press(KeyCode::ControlRight, Key::Named(NamedKey::Control));
+    assert!(headless.modifiers().shift_key());
+    assert!(headless.modifiers().control_key());
+
+    headless.key_release(KeyCode::ShiftLeft, Key::Named(NamedKey::Shift));
+    assert!(!headless.modifiers().shift_key());
+    assert!(headless.modifiers().control_key());
+
+    headless.key_release(KeyCode::ControlRight, Key::Named(NamedKey::Control));
+    assert!(headless.modifiers().is_empty());
+}
+
+#[test]
+fn cursor_follows_touch_in_logical_pixels() {
+    let mut headless = headless();
+    assert_eq!(headless.cursor(), mouse::Cursor::Unavailable);
+
+    headless.touch(0, TouchPhase::Started, 262.5, 525.0);
+    assert_eq!(
+        headless.cursor(),
+        mouse::Cursor::Available(Point::new(100.0, 200.0))
+    );
+
+    headless.touch(0, TouchPhase::Moved, 525.0, 1050.0);
+    assert_eq!(
+        headless.cursor(),
+        mouse::Cursor::Available(Point::new(200.0, 400.0))
+    );
+}
+
+#[test]
+fn messages_update_controls() {
+    let mut headless = headless();
+
+    headless.message(Message::ExampleSelected(Example::Counter));
+    headless.message(Message::Inc);
+    headless.message(Message::Inc);
+    headless.message(Message::Dec);
+
+    assert_eq!(headless.program().selected_example(), Example::Counter);
+    assert_eq!(headless.program().value(), 1);
+}
+
+#[test]
+fn tasks_are_performed() {
+    let mut headless = headless();
+
+    headless.message(Message::RedChanged(0.5));
+    headless.message(Message::CopyColor);
+
+    let expected = format!("{:?}", Color::from_rgb(0.5, 0.0, 0.0));
+    assert_eq!(headless.clipboard(), Some(expected.as_str()));
+}
+
+#[test]
+fn clock_runs_in_counter_example() {
+    let mut headless = headless();
+    assert!(subscriptions(&headless).is_empty());
+
+    headless.message(Message::ExampleSelected(Example::Counter));
+    assert_eq!(subscriptions(&headless).len(), 1);
+
+    headless.message(Message::Tick);
+    headless.message(Message::Tick);
+    assert_eq!(headless.program().seconds(), 2);
+}
+
+#[test]
+fn clock_waits_for_resume() {
+    let mut headless = headless();
+    headless.suspend();
+
+    // e.g. the output of a task, which arrives in background
+    headless.message(Message::ExampleSelected(Example::Counter));
+    assert_eq!(subscriptions(&headless).len(), 1);
+    assert_eq!(headless.subscriptions(), 0);
+
+    headless.resume();
+    assert_eq!(headless.subscriptions(), 1);
+
+    headless.suspend();
+    assert_eq!(headless.subscriptions(), 0);
+}
+
+fn subscriptions(headless: &Headless<Controls>) -> Vec<Box<dyn Recipe<Output = Message>>> {
+    subscription::into_recipes(Program::subscription(headless.program()))
+}
+
+#[test]
+fn theme_follows_system_appearance() {
+    let mut headless = headless();
+    assert_eq!(headless.theme(), Theme::Light);
+
+    headless.set_appearance(Appearance::Dark);
+
+    assert_eq!(headless.theme(), Theme::Dark);
+}
+
+#[test]
+fn appearance_keeps_the_picked_background() {
+    let mut headless = headless();
+    headless.message(Message::RedChanged(0.5));
+
+    headless.set_appearance(Appearance::Dark);
+
+    assert_eq!(
+        Program::background_color(headless.program()),
+        Color::from_rgb(0.5, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn config_theme_overrides_system_appearance() {
+    let config = AppConfig::default().theme(Theme::Ferra);
+    let mut headless =
+        Headless::with_config(Controls::default(), config, WIDTH, HEIGHT, SCALE_FACTOR);
+
+    headless.set_appearance(Appearance::Dark);
+
+    assert_eq!(headless.theme(), Theme::Ferra);
+}
+
+#[test]
+fn typing_into_text_editor() {
+    let mut headless = headless();
+    headless.message(Message::ExampleSelected(Example::TextEditor));
+
+    // the editor is in the middle of the screen
+    headless.click(WIDTH as f64 / 2.0, HEIGHT as f64 / 2.0);
+    headless.type_text("hello");
+
+    assert!(headless.program().editor_text().starts_with("hello"));
+}
+
+#[test]
+fn resize_keeps_state() {
+    let mut headless = headless();
+    headless.message(Message::Inc);
+
+    headless.resize(HEIGHT, WIDTH);
+
+    assert_eq!(headless.program().value(), 1);
+}
+
+#[test]
+fn screenshot_is_rendered_in_physical_pixels() {
+    let mut headless = headless();
+    headless.message(Message::RedChanged(1.0));
+
+    let screenshot = headless.screenshot().unwrap();
+    assert_eq!(screenshot.width(), WIDTH);
+    assert_eq!(screenshot.height(), HEIGHT);
+    assert_eq!(screenshot.pixel(0, 0), [255, 0, 0, 255]);
+
+    let png = screenshot.encode_png().unwrap();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+}
+
+#[test]
+fn no_screenshot_of_an_empty_window() {
+    let mut headless = headless();
+
+    headless.resize(0, 0);
+
+    assert!(headless.screenshot().is_none());
+}
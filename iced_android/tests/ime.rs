@@ -0,0 +1,239 @@
+//! Composition of an input method, drawn over the focused text widget
+//! and typed into it once committed.
+
+use iced_android::controls::{Controls, Example, Message as ControlsMessage};
+use iced_android::headless::Headless;
+use iced_android::shortcut::History;
+use iced_android::Shortcut;
+use iced_widget::{column, text_input, Renderer};
+use iced_winit::core::{Element, Theme};
+use iced_winit::runtime::{Program, Task};
+use iced_winit::winit::event::Ime;
+use iced_winit::winit::keyboard::{Key, KeyCode, NamedKey};
+
+// a text field at the top of the screen, with undo
+#[derive(Default)]
+struct Field {
+    value: String,
+    history: History<String>,
+    // an input method is composing, its steps aren't recorded
+    composing: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Changed(String),
+    Shortcut(Shortcut),
+    Composing(bool),
+}
+
+impl Program for Field {
+    type Theme = Theme;
+    type Message = Message;
+    type Renderer = Renderer;
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Changed(value) => {
+                let previous = std::mem::replace(&mut self.value, value);
+                if !self.composing {
+                    self.history.record(previous);
+                }
+            }
+            Message::Shortcut(Shortcut::Undo) => {
+                if let Some(value) = self.history.undo(self.value.clone()) {
+                    self.value = value;
+                }
+            }
+            Message::Shortcut(Shortcut::Redo) => {
+                if let Some(value) = self.history.redo(self.value.clone()) {
+                    self.value = value;
+                }
+            }
+            Message::Composing(composing) => {
+                if composing {
+                    self.history.record(self.value.clone());
+                }
+                self.composing = composing;
+            }
+        }
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Message, Theme, Renderer> {
+        column![text_input("", &self.value).on_input(Message::Changed)].into()
+    }
+}
+
+impl iced_android::Program for Field {
+    type Scene = ();
+
+    fn shortcut(&self, shortcut: Shortcut) -> Option<Message> {
+        Some(Message::Shortcut(shortcut))
+    }
+
+    fn composing(&self, composing: bool) -> Option<Message> {
+        Some(Message::Composing(composing))
+    }
+}
+
+fn field() -> Headless<Field> {
+    let mut headless = Headless::new(Field::default(), 400, 800, 2.0);
+    headless.tap(100.0, 20.0);
+    headless.ime(Ime::Enabled);
+    headless
+}
+
+fn preedit(text: &str) -> Ime {
+    Ime::Preedit(text.to_string(), Some((text.len(), text.len())))
+}
+
+#[test]
+fn preedit_is_shown_and_committed() {
+    let mut headless = field();
+    headless.type_text("a");
+
+    headless.ime(preedit("n"));
+    assert_eq!(headless.preedit(), Some("n"));
+    headless.ime(preedit("ni"));
+    headless.ime(preedit("nihao"));
+    assert_eq!(headless.preedit(), Some("nihao"));
+    assert_eq!(headless.program().value, "a");
+
+    // like winit on desktop, the preedit is cleared before the commit
+    headless.ime(preedit(""));
+    assert_eq!(headless.preedit(), None);
+    headless.ime(Ime::Commit("你好".to_string()));
+    assert_eq!(headless.program().value, "a你好");
+
+    headless.type_text("!");
+    assert_eq!(headless.program().value, "a你好!");
+}
+
+#[test]
+fn swiped_words_are_committed() {
+    let mut headless = field();
+
+    // swipe typing
+    headless.ime(preedit("wor"));
+    headless.ime(preedit("world"));
+    headless.ime(Ime::Commit("world ".to_string()));
+    headless.ime(preedit("h"));
+    headless.ime(Ime::Commit("hello".to_string()));
+
+    assert_eq!(headless.program().value, "world hello");
+}
+
+#[test]
+fn preedit_is_underlined_at_the_caret() {
+    let mut headless = field();
+    let before = headless.screenshot().unwrap();
+
+    headless.ime(preedit("nihao"));
+    let composing = headless.screenshot().unwrap();
+    let area = headless.ime_cursor_area().expect("cursor area");
+
+    // the line under the text, in physical pixels
+    let x = (area.x + area.width / 2.0) as u32 * 2;
+    let y = ((area.y + area.height) * 2.0) as u32 - 1;
+    assert_ne!(composing.pixel(x, y), before.pixel(x, y));
+    assert_eq!(composing.pixel(x, y), [0, 0, 0, 255]);
+
+    headless.ime(Ime::Commit("nihao".to_string()));
+    assert_eq!(headless.preedit(), None);
+}
+
+#[test]
+fn disabling_keeps_the_preedit() {
+    let mut headless = field();
+
+    headless.ime(preedit("abc"));
+    headless.ime(Ime::Disabled);
+    headless.type_text("d");
+
+    assert_eq!(headless.program().value, "abcd");
+}
+
+#[test]
+fn composing_in_text_editor() {
+    let mut headless = Headless::new(Controls::default(), 1080, 2400, 2.625);
+    headless.message(ControlsMessage::ExampleSelected(Example::TextEditor));
+    headless.click(540.0, 1200.0);
+    headless.ime(Ime::Enabled);
+
+    headless.ime(preedit("にほ"));
+    headless.ime(preedit("にほん"));
+    assert_eq!(headless.preedit(), Some("にほん"));
+    assert_eq!(headless.program().editor_text().trim_end(), "");
+
+    headless.ime(Ime::Commit("日本".to_string()));
+    headless.type_text("!");
+    assert_eq!(headless.program().editor_text().trim_end(), "日本!");
+}
+
+#[test]
+fn candidate_window_is_placed_at_the_caret() {
+    let mut headless = field();
+    headless.ime(preedit("n"));
+
+    let area = headless.ime_cursor_area().expect("cursor area");
+    // the field is empty, the caret is at its start, not where it was tapped
+    // at (100, 20) physical pixels, with a scale factor of 2
+    assert!(area.x < 10.0);
+    assert!(area.y < 10.0 && area.y + area.height > 10.0);
+}
+
+#[test]
+fn candidate_window_follows_the_caret() {
+    let mut headless = field();
+    headless.ime(preedit("ni"));
+    let start = headless.ime_cursor_area().expect("cursor area");
+
+    headless.ime(Ime::Commit("你".to_string()));
+    headless.type_text("a");
+    headless.ime(preedit("h"));
+    let typed = headless.ime_cursor_area().expect("cursor area");
+
+    assert!(typed.x > start.x + 16.0);
+    assert_eq!(typed.y, start.y);
+}
+
+#[test]
+fn composition_is_undone_at_once() {
+    let mut headless = field();
+    headless.type_text("a");
+
+    headless.ime(preedit("n"));
+    headless.ime(preedit("ni"));
+    headless.ime(preedit("nihao"));
+    headless.ime(preedit(""));
+    headless.ime(Ime::Commit("你好".to_string()));
+    assert_eq!(headless.program().value, "a你好");
+
+    headless.key_press(KeyCode::ControlLeft, Key::Named(NamedKey::Control));
+    headless.key_press(KeyCode::KeyZ, Key::Character("z".into()));
+    headless.key_release(KeyCode::KeyZ, Key::Character("z".into()));
+    headless.key_release(KeyCode::ControlLeft, Key::Named(NamedKey::Control));
+
+    assert_eq!(headless.program().value, "a");
+}
+
+#[test]
+fn moving_the_caret_finishes_the_composition() {
+    let mut headless = field();
+    headless.type_text("a");
+    headless.ime(preedit("ni"));
+
+    // at the start of the field, the composing text is typed where it was
+    headless.tap(4.0, 20.0);
+    assert_eq!(headless.program().value, "ani");
+
+    // the rest of the composition isn't shown, its commit goes to the caret
+    headless.ime(preedit("nih"));
+    assert_eq!(headless.preedit(), None);
+    headless.ime(Ime::Commit("你".to_string()));
+    assert_eq!(headless.program().value, "你ani");
+
+    headless.type_text("x");
+    assert_eq!(headless.program().value, "你xani");
+}
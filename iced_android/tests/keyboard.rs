@@ -0,0 +1,312 @@
+//! Modifiers tracked from key events and the editing shortcuts of text fields,
+//! like with the keyboard of a Chromebook or a tablet.
+
+use iced_android::controls::{Controls, Example, Message as ControlsMessage};
+use iced_android::headless::Headless;
+use iced_android::shortcut::History;
+use iced_android::Shortcut;
+use iced_widget::{column, text_input, Renderer};
+use iced_winit::core::{Element, Theme};
+use iced_winit::runtime::{Program, Task};
+use iced_winit::winit::keyboard::{Key, KeyCode, NamedKey};
+
+// a text field at the top of the screen, with undo
+#[derive(Default)]
+struct Field {
+    value: String,
+    history: History<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Changed(String),
+    Shortcut(Shortcut),
+}
+
+impl Program for Field {
+    type Theme = Theme;
+    type Message = Message;
+    type Renderer = Renderer;
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Changed(value) => {
+                let previous = std::mem::replace(&mut self.value, value);
+                self.history.record(previous);
+            }
+            Message::Shortcut(Shortcut::Undo) => {
+                if let Some(value) = self.history.undo(self.value.clone()) {
+                    self.value = value;
+                }
+            }
+            Message::Shortcut(Shortcut::Redo) => {
+                if let Some(value) = self.history.redo(self.value.clone()) {
+                    self.value = value;
+                }
+            }
+        }
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Message, Theme, Renderer> {
+        column![text_input("", &self.value).on_input(Message::Changed)].into()
+    }
+}
+
+impl iced_android::Program for Field {
+    type Scene = ();
+
+    fn shortcut(&self, shortcut: Shortcut) -> Option<Message> {
+        Some(Message::Shortcut(shortcut))
+    }
+}
+
+fn field() -> Headless<Field> {
+    let mut headless = Headless::new(Field::default(), 400, 800, 2.0);
+    headless.tap(100.0, 20.0);
+    headless
+}
+
+fn editor() -> Headless<Controls> {
+    let mut headless = Headless::new(Controls::default(), 1080, 2400, 2.625);
+    headless.message(ControlsMessage::ExampleSelected(Example::TextEditor));
+    // the editor is in the middle of the screen
+    headless.click(540.0, 1200.0);
+    headless
+}
+
+fn press(headless: &mut Headless<impl iced_android::Program>, code: KeyCode, key: Key) {
+    headless.key_press(code, key.clone());
+    headless.key_release(code, key);
+}
+
+// a key with a modifier held
+fn chord<P: iced_android::Program>(
+    headless: &mut Headless<P>,
+    (modifier_code, modifier): (KeyCode, NamedKey),
+    code: KeyCode,
+    key: Key,
+) {
+    headless.key_press(modifier_code, Key::Named(modifier));
+    press(headless, code, key);
+    headless.key_release(modifier_code, Key::Named(modifier));
+}
+
+const CTRL: (KeyCode, NamedKey) = (KeyCode::ControlLeft, NamedKey::Control);
+const SEARCH: (KeyCode, NamedKey) = (KeyCode::SuperLeft, NamedKey::Super);
+const FN: (KeyCode, NamedKey) = (KeyCode::Fn, NamedKey::Fn);
+
+fn character(c: &str) -> Key {
+    Key::Character(c.into())
+}
+
+#[test]
+fn every_modifier_is_tracked() {
+    let mut headless = field();
+
+    headless.key_press(KeyCode::AltLeft, Key::Named(NamedKey::Alt));
+    headless.key_press(KeyCode::SuperLeft, Key::Named(NamedKey::Super));
+    assert!(headless.modifiers().alt_key());
+    assert!(headless.modifiers().super_key());
+
+    headless.key_release(KeyCode::AltLeft, Key::Named(NamedKey::Alt));
+    headless.key_release(KeyCode::SuperLeft, Key::Named(NamedKey::Super));
+    assert!(headless.modifiers().is_empty());
+
+    headless.key_press(KeyCode::Fn, Key::Named(NamedKey::Fn));
+    assert!(headless.fn_key());
+    headless.key_release(KeyCode::Fn, Key::Named(NamedKey::Fn));
+    assert!(!headless.fn_key());
+
+    // toggled by the press
+    press(
+        &mut headless,
+        KeyCode::CapsLock,
+        Key::Named(NamedKey::CapsLock),
+    );
+    assert!(headless.caps_lock());
+    press(
+        &mut headless,
+        KeyCode::CapsLock,
+        Key::Named(NamedKey::CapsLock),
+    );
+    assert!(!headless.caps_lock());
+}
+
+#[test]
+fn both_shift_keys_are_tracked() {
+    let mut headless = field();
+    let shift = Key::Named(NamedKey::Shift);
+
+    headless.key_press(KeyCode::ShiftLeft, shift.clone());
+    headless.key_press(KeyCode::ShiftRight, shift.clone());
+    headless.key_release(KeyCode::ShiftLeft, shift.clone());
+    assert!(headless.modifiers().shift_key());
+
+    headless.key_release(KeyCode::ShiftRight, shift);
+    assert!(headless.modifiers().is_empty());
+}
+
+#[test]
+fn caps_lock_types_uppercase() {
+    let mut headless = field();
+
+    press(
+        &mut headless,
+        KeyCode::CapsLock,
+        Key::Named(NamedKey::CapsLock),
+    );
+    headless.type_text("ab");
+    press(
+        &mut headless,
+        KeyCode::CapsLock,
+        Key::Named(NamedKey::CapsLock),
+    );
+    headless.type_text("c");
+
+    assert_eq!(headless.program().value, "ABc");
+}
+
+#[test]
+fn ctrl_a_selects_everything_in_text_input() {
+    let mut headless = field();
+    headless.type_text("hello world");
+
+    chord(&mut headless, CTRL, KeyCode::KeyA, character("a"));
+    headless.type_text("x");
+
+    assert_eq!(headless.program().value, "x");
+}
+
+#[test]
+fn ctrl_x_and_ctrl_v_use_the_clipboard() {
+    let mut headless = field();
+    headless.type_text("hello");
+
+    chord(&mut headless, CTRL, KeyCode::KeyA, character("a"));
+    chord(&mut headless, CTRL, KeyCode::KeyX, character("x"));
+    assert_eq!(headless.program().value, "");
+    assert_eq!(headless.clipboard(), Some("hello"));
+
+    chord(&mut headless, CTRL, KeyCode::KeyV, character("v"));
+    chord(&mut headless, CTRL, KeyCode::KeyV, character("v"));
+    assert_eq!(headless.program().value, "hellohello");
+}
+
+#[test]
+fn ctrl_arrow_jumps_over_words() {
+    let mut headless = field();
+    headless.type_text("hello world");
+
+    chord(
+        &mut headless,
+        CTRL,
+        KeyCode::ArrowLeft,
+        Key::Named(NamedKey::ArrowLeft),
+    );
+    headless.type_text("big ");
+
+    assert_eq!(headless.program().value, "hello big world");
+}
+
+#[test]
+fn fn_and_search_arrows_are_home_and_end() {
+    let mut headless = field();
+    headless.type_text("world");
+
+    chord(
+        &mut headless,
+        FN,
+        KeyCode::ArrowLeft,
+        Key::Named(NamedKey::ArrowLeft),
+    );
+    headless.type_text("hello ");
+    assert_eq!(headless.program().value, "hello world");
+
+    chord(
+        &mut headless,
+        SEARCH,
+        KeyCode::ArrowLeft,
+        Key::Named(NamedKey::ArrowLeft),
+    );
+    // Search+Backspace deletes forward
+    chord(
+        &mut headless,
+        SEARCH,
+        KeyCode::Backspace,
+        Key::Named(NamedKey::Backspace),
+    );
+    chord(
+        &mut headless,
+        SEARCH,
+        KeyCode::ArrowRight,
+        Key::Named(NamedKey::ArrowRight),
+    );
+    headless.type_text("!");
+    assert_eq!(headless.program().value, "ello world!");
+}
+
+#[test]
+fn undo_and_redo_in_text_input() {
+    let mut headless = field();
+    headless.type_text("abc");
+
+    chord(&mut headless, CTRL, KeyCode::KeyZ, character("z"));
+    chord(&mut headless, CTRL, KeyCode::KeyZ, character("z"));
+    // the shortcut doesn't type
+    assert_eq!(headless.program().value, "a");
+
+    chord(&mut headless, CTRL, KeyCode::KeyY, character("y"));
+    assert_eq!(headless.program().value, "ab");
+
+    // Ctrl+Shift+Z redoes too
+    headless.key_press(KeyCode::ShiftLeft, Key::Named(NamedKey::Shift));
+    chord(&mut headless, CTRL, KeyCode::KeyZ, character("z"));
+    headless.key_release(KeyCode::ShiftLeft, Key::Named(NamedKey::Shift));
+    assert_eq!(headless.program().value, "abc");
+
+    // nothing left to redo
+    chord(&mut headless, CTRL, KeyCode::KeyY, character("y"));
+    assert_eq!(headless.program().value, "abc");
+}
+
+#[test]
+fn undo_in_text_editor() {
+    let mut headless = editor();
+    headless.type_text("ab");
+
+    chord(&mut headless, CTRL, KeyCode::KeyZ, character("z"));
+    assert_eq!(headless.program().editor_text().trim_end(), "a");
+
+    chord(&mut headless, CTRL, KeyCode::KeyY, character("y"));
+    assert_eq!(headless.program().editor_text().trim_end(), "ab");
+}
+
+#[test]
+fn undo_in_text_editor_restores_the_cursor() {
+    let mut headless = editor();
+    headless.type_text("one");
+    press(&mut headless, KeyCode::Enter, Key::Named(NamedKey::Enter));
+    headless.type_text("two");
+
+    chord(&mut headless, CTRL, KeyCode::KeyZ, character("z"));
+    // where it was before the "o" was typed
+    headless.type_text("!");
+
+    assert_eq!(headless.program().editor_text().trim_end(), "one\ntw!");
+}
+
+#[test]
+fn ctrl_backspace_deletes_a_word_in_text_editor() {
+    let mut headless = editor();
+    headless.type_text("hello world");
+
+    chord(
+        &mut headless,
+        CTRL,
+        KeyCode::Backspace,
+        Key::Named(NamedKey::Backspace),
+    );
+
+    assert_eq!(headless.program().editor_text().trim_end(), "hello");
+}
@@ -0,0 +1,143 @@
+//! Flings over a scrollable, driven by the fake clock of the harness.
+
+use std::time::Duration;
+
+use iced_android::headless::Headless;
+use iced_android::AppConfig;
+use iced_widget::scrollable::Viewport;
+use iced_widget::{column, scrollable, text, Renderer};
+use iced_winit::core::{Element, Length, Theme};
+use iced_winit::runtime::{Program, Task};
+use iced_winit::winit::event::TouchPhase;
+
+const MS: Duration = Duration::from_millis(1);
+
+// 100 rows of 50 logical pixels in a 200x400 window
+#[derive(Default)]
+struct Feed {
+    offset: f32,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Scrolled(Viewport),
+}
+
+impl Program for Feed {
+    type Theme = Theme;
+    type Message = Message;
+    type Renderer = Renderer;
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Scrolled(viewport) => self.offset = viewport.absolute_offset().y,
+        }
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Message, Theme, Renderer> {
+        scrollable(column(
+            (0..100).map(|i| text!("row {i}").height(50).width(Length::Fill).into()),
+        ))
+        .on_scroll(Message::Scrolled)
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+impl iced_android::Program for Feed {
+    type Scene = ();
+}
+
+fn headless() -> Headless<Feed> {
+    Headless::new(Feed::default(), 400, 800, 2.0)
+}
+
+// physical pixels, 10 steps of 10ms, the finger moves at 2500 logical pixels per second
+fn drag(headless: &mut Headless<Feed>, from: f64, to: f64) {
+    headless.touch(0, TouchPhase::Started, 200.0, from);
+
+    for step in 1..=10 {
+        headless.advance(10 * MS);
+        let y = from + (to - from) * f64::from(step) / 10.0;
+        headless.touch(0, TouchPhase::Moved, 200.0, y);
+    }
+
+    headless.touch(0, TouchPhase::Ended, 200.0, to);
+}
+
+#[test]
+fn fling_keeps_scrolling_and_slows_down() {
+    let mut headless = headless();
+
+    drag(&mut headless, 700.0, 200.0);
+    // the drag itself
+    assert_eq!(headless.program().offset, 250.0);
+    assert!(headless.is_scrolling());
+
+    headless.advance(100 * MS);
+    let first = headless.program().offset - 250.0;
+    headless.advance(100 * MS);
+    let second = headless.program().offset - 250.0 - first;
+
+    assert!(first > 100.0, "{first}");
+    assert!(second < first, "{second} after {first}");
+
+    headless.advance(Duration::from_secs(10));
+    let offset = headless.program().offset;
+    // v / friction in total
+    assert!((offset - 250.0 - 1250.0).abs() < 30.0, "{offset}");
+    assert!(!headless.is_scrolling());
+}
+
+#[test]
+fn touch_stops_fling() {
+    let mut headless = headless();
+
+    drag(&mut headless, 700.0, 200.0);
+    headless.advance(100 * MS);
+    headless.touch(1, TouchPhase::Started, 200.0, 400.0);
+
+    let offset = headless.program().offset;
+    assert!(!headless.is_scrolling());
+
+    headless.advance(Duration::from_secs(1));
+    assert_eq!(headless.program().offset, offset);
+}
+
+#[test]
+fn fling_stops_at_the_edge_with_a_glow() {
+    let mut headless = headless();
+
+    // towards the start, which is already shown
+    drag(&mut headless, 200.0, 700.0);
+    let before = headless.screenshot().unwrap();
+
+    headless.advance(50 * MS);
+    assert_eq!(headless.program().offset, 0.0);
+    assert!(headless.is_scrolling());
+
+    // the top edge is tinted, the bottom one isn't
+    let glowing = headless.screenshot().unwrap();
+    assert_ne!(glowing.pixel(200, 2), before.pixel(200, 2));
+    assert_eq!(glowing.pixel(200, 797), before.pixel(200, 797));
+
+    headless.advance(Duration::from_secs(2));
+    assert!(!headless.is_scrolling());
+    assert_eq!(
+        headless.screenshot().unwrap().pixel(200, 2),
+        before.pixel(200, 2)
+    );
+}
+
+#[test]
+fn kinetic_scrolling_can_be_disabled() {
+    let config = AppConfig::default().kinetic_scrolling(None);
+    let mut headless = Headless::with_config(Feed::default(), config, 400, 800, 2.0);
+
+    drag(&mut headless, 700.0, 200.0);
+    headless.advance(Duration::from_secs(1));
+
+    assert_eq!(headless.program().offset, 250.0);
+    assert!(!headless.is_scrolling());
+}
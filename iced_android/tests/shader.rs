@@ -0,0 +1,20 @@
+use iced_android::shader;
+
+#[test]
+fn bundled_shaders_are_valid() {
+    let source = std::fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/assets/shaders/animated.wgsl"
+    ))
+    .unwrap();
+
+    shader::validate(&source).unwrap();
+}
+
+#[test]
+fn errors_point_to_the_source() {
+    let source = "@fragment\nfn fs_main() -> @location(0) vec4<f32> {\n    return vec3<f32>(1.0);\n}\n";
+
+    let error = shader::validate(source).unwrap_err();
+    assert!(error.contains("return vec3<f32>(1.0);"), "{error}");
+}
@@ -0,0 +1,238 @@
+//! Synthetic touch sequences, checking the fingers seen by the widgets
+//! and the cursor emulated from the primary finger.
+
+use iced_android::headless::Headless;
+use iced_widget::{button, column, Renderer};
+use iced_winit::core::layout::{self, Layout};
+use iced_winit::core::widget::{tree, Tree};
+use iced_winit::core::{
+    event, mouse, renderer, touch, Clipboard, Element, Event, Length, Point, Rectangle, Shell,
+    Size, Theme, Widget,
+};
+use iced_winit::runtime::{Program, Task};
+use iced_winit::winit::event::TouchPhase;
+
+const WIDTH: u32 = 400;
+const HEIGHT: u32 = 800;
+const SCALE_FACTOR: f64 = 2.0;
+
+// physical pixels, the button covers the top half of the screen
+const BUTTON: (f64, f64) = (200.0, 200.0);
+const BELOW_BUTTON: (f64, f64) = (200.0, 600.0);
+
+#[derive(Default)]
+struct Touches {
+    presses: usize,
+    events: Vec<touch::Event>,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Pressed,
+    Touch(touch::Event),
+}
+
+impl Program for Touches {
+    type Theme = Theme;
+    type Message = Message;
+    type Renderer = Renderer;
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Pressed => self.presses += 1,
+            Message::Touch(event) => self.events.push(event),
+        }
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Message, Theme, Renderer> {
+        column![
+            button("press")
+                .on_press(Message::Pressed)
+                .width(Length::Fill)
+                .height(Length::Fill),
+            Element::new(Recorder),
+        ]
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+impl iced_android::Program for Touches {
+    type Scene = ();
+}
+
+// publishes every touch event, wherever the finger is
+struct Recorder;
+
+impl Widget<Message, Theme, Renderer> for Recorder {
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fill, Length::Fill)
+    }
+
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::stateless()
+    }
+
+    fn layout(
+        &self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::Node::new(limits.max())
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        _renderer: &mut Renderer,
+        _theme: &Theme,
+        _style: &renderer::Style,
+        _layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+    }
+
+    fn on_event(
+        &mut self,
+        _tree: &mut Tree,
+        event: Event,
+        _layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) -> event::Status {
+        if let Event::Touch(event) = event {
+            shell.publish(Message::Touch(event));
+        }
+        event::Status::Ignored
+    }
+}
+
+fn headless() -> Headless<Touches> {
+    Headless::new(Touches::default(), WIDTH, HEIGHT, SCALE_FACTOR)
+}
+
+fn logical((x, y): (f64, f64)) -> Point {
+    Point::new((x / SCALE_FACTOR) as f32, (y / SCALE_FACTOR) as f32)
+}
+
+#[test]
+fn tap_presses_button_and_resets_cursor() {
+    let mut headless = headless();
+
+    headless.touch(0, TouchPhase::Started, BUTTON.0, BUTTON.1);
+    assert_eq!(headless.mouse_interaction(), mouse::Interaction::Pointer);
+
+    headless.touch(0, TouchPhase::Ended, BUTTON.0, BUTTON.1);
+
+    assert_eq!(headless.program().presses, 1);
+    assert_eq!(headless.cursor(), mouse::Cursor::Unavailable);
+    // nothing stays hovered
+    assert_eq!(headless.mouse_interaction(), mouse::Interaction::None);
+    assert_eq!(headless.fingers(), 0);
+}
+
+#[test]
+fn cancelled_touch_does_not_press_button() {
+    let mut headless = headless();
+
+    headless.touch(0, TouchPhase::Started, BUTTON.0, BUTTON.1);
+    headless.touch(0, TouchPhase::Cancelled, BUTTON.0, BUTTON.1);
+
+    assert_eq!(headless.program().presses, 0);
+    assert_eq!(headless.cursor(), mouse::Cursor::Unavailable);
+}
+
+#[test]
+fn second_finger_does_not_move_cursor() {
+    let mut headless = headless();
+
+    headless.touch(0, TouchPhase::Started, BUTTON.0, BUTTON.1);
+    headless.touch(1, TouchPhase::Started, BELOW_BUTTON.0, BELOW_BUTTON.1);
+    headless.touch(1, TouchPhase::Moved, BELOW_BUTTON.0, BELOW_BUTTON.1 + 10.0);
+
+    assert_eq!(headless.fingers(), 2);
+    assert_eq!(headless.cursor(), mouse::Cursor::Available(logical(BUTTON)));
+
+    headless.touch(0, TouchPhase::Moved, BUTTON.0 + 10.0, BUTTON.1);
+    assert_eq!(
+        headless.cursor(),
+        mouse::Cursor::Available(logical((BUTTON.0 + 10.0, BUTTON.1)))
+    );
+}
+
+#[test]
+fn no_finger_takes_over_until_all_are_lifted() {
+    let mut headless = headless();
+
+    headless.touch(0, TouchPhase::Started, BUTTON.0, BUTTON.1);
+    headless.touch(1, TouchPhase::Started, BELOW_BUTTON.0, BELOW_BUTTON.1);
+    headless.touch(0, TouchPhase::Ended, BUTTON.0, BUTTON.1);
+
+    assert_eq!(headless.fingers(), 1);
+    assert_eq!(headless.cursor(), mouse::Cursor::Unavailable);
+
+    headless.touch(1, TouchPhase::Moved, BUTTON.0, BUTTON.1);
+    assert_eq!(headless.cursor(), mouse::Cursor::Unavailable);
+
+    headless.touch(1, TouchPhase::Ended, BUTTON.0, BUTTON.1);
+    headless.touch(2, TouchPhase::Started, BELOW_BUTTON.0, BELOW_BUTTON.1);
+
+    assert_eq!(
+        headless.cursor(),
+        mouse::Cursor::Available(logical(BELOW_BUTTON))
+    );
+}
+
+#[test]
+fn second_finger_lift_does_not_press_button() {
+    let mut headless = headless();
+
+    headless.touch(0, TouchPhase::Started, BELOW_BUTTON.0, BELOW_BUTTON.1);
+    headless.touch(1, TouchPhase::Started, BUTTON.0, BUTTON.1);
+    headless.touch(1, TouchPhase::Ended, BUTTON.0, BUTTON.1);
+
+    assert_eq!(headless.program().presses, 0);
+}
+
+#[test]
+fn every_touch_event_reaches_widgets() {
+    let mut headless = headless();
+
+    headless.touch(0, TouchPhase::Started, 100.0, 100.0);
+    headless.touch(1, TouchPhase::Started, 300.0, 700.0);
+    headless.touch(1, TouchPhase::Moved, 300.0, 600.0);
+    headless.touch(0, TouchPhase::Ended, 100.0, 100.0);
+    headless.touch(1, TouchPhase::Cancelled, 300.0, 600.0);
+
+    assert_eq!(
+        headless.program().events,
+        [
+            touch::Event::FingerPressed {
+                id: touch::Finger(0),
+                position: Point::new(50.0, 50.0),
+            },
+            touch::Event::FingerPressed {
+                id: touch::Finger(1),
+                position: Point::new(150.0, 350.0),
+            },
+            touch::Event::FingerMoved {
+                id: touch::Finger(1),
+                position: Point::new(150.0, 300.0),
+            },
+            touch::Event::FingerLifted {
+                id: touch::Finger(0),
+                position: Point::new(50.0, 50.0),
+            },
+            touch::Event::FingerLost {
+                id: touch::Finger(1),
+                position: Point::new(150.0, 300.0),
+            },
+        ]
+    );
+}